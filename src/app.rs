@@ -1,30 +1,151 @@
 use egui::{Color32, Context, CornerRadius, RichText, Stroke, Ui, Vec2};
 use core::f32;
 use std::sync::mpsc::{channel, Receiver};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use crate::autostart::{self, AutostartStatus};
+use crate::cli::{Command, LaunchArgs};
 use crate::config;
 use crate::hotkeys::{HotKeyAction, HotKeySystem};
-use crate::tracker::MouseTracker;
+use crate::instance::{InstanceListener, InstanceLock};
+use crate::notifications::{NotificationEvent, Notifier};
+use crate::export;
+use crate::markers::MarkerExport;
+use crate::monitors::{self, Monitor, MonitorWatcher};
+use crate::overlay::{MarkerOverlay, RestorePulse};
+use crate::path::{self, CursorPath};
+use crate::replay::{Replay, ReplayState};
+use crate::rules::{ExclusionZone, SaveFilter};
+use crate::session::SessionLog;
+use crate::settings::Settings;
+use crate::tour::TourPlayer;
+use crate::tracker::{MouseTracker, SavedPosition, TrackerEvent};
+use crate::tray::{TrayState, TraySystem};
+use crate::windows::FocusTracker;
+
+mod settings_view;
 
 // Main application state
 pub struct MouseMinderApp {
     tracker: MouseTracker,
     hotkey_receiver: Receiver<HotKeyAction>,
+    _hotkey_system: Option<HotKeySystem>,
+    hotkey_error: Option<String>,
+    command_receiver: Receiver<Command>,
+    _instance_listener: Option<InstanceListener>, // Hold the single instance lock
+    tray: Option<TraySystem>,
+    marker_overlay: MarkerOverlay,
+    restore_pulse: RestorePulse,
+    notifier: Notifier,
+    recording_started: Option<Instant>,
+    session: SessionLog,
+    last_path: Option<CursorPath>,
+    replay: Option<Replay>,
+    tour: TourPlayer,
+    tour_error: Option<String>,
+    slot_import_path: String,
+    slot_status: Option<Result<String, String>>,
+    monitors: Vec<Monitor>,
+    monitor_watcher: Option<MonitorWatcher>,
+    focus: FocusTracker,
+    export_status: Option<Result<String, String>>,
+    settings: Settings,
+    settings_open: bool,
+    autostart_status: AutostartStatus,
+    autostart_error: Option<String>,
+    start_hidden: bool,
+    quitting: bool,
     last_restore_time: Option<SystemTime>,
     restore_feedback_visible: bool,
+    restore_adjustment: Option<String>,
+    save_skipped: Option<String>,
+    // When paused tracking starts again by itself
+    tracking_resume_at: Option<Instant>,
+    // Hidden to the tray, egui reports minimizing but not hiding
+    window_hidden: bool,
 }
 
 impl MouseMinderApp {
-    pub fn new(ctx: &Context) -> Self {
+    pub fn new(
+        ctx: &Context,
+        settings: Settings,
+        instance_lock: Option<InstanceLock>,
+        launch: LaunchArgs,
+    ) -> Self {
         // Create action channel for hotkey events
         let (tx, rx) = channel();
 
+        // Commands come from our own launch arguments and from later launches
+        let (command_tx, command_rx) = channel();
+        for command in launch.commands {
+            let _ = command_tx.send(command);
+        }
+
+        // The tray is optional, e.g. there may be no StatusNotifierItem host
+        let tray = match TraySystem::new(command_tx.clone(), ctx.clone()) {
+            Ok(tray) => Some(tray),
+            Err(err) => {
+                eprintln!("System tray unavailable: {}", err);
+                None
+            }
+        };
+
+        let instance_listener = instance_lock.map(|lock| lock.listen(command_tx, ctx.clone()));
+
+        // Warn about a login entry left behind by a moved or reinstalled binary
+        let autostart_status = autostart::status();
+        if let AutostartStatus::Stale(program) = &autostart_status {
+            eprintln!("Autostart entry points to a different binary: {}", program);
+        }
+        let start_hidden = launch.daemon || settings.start_minimized;
+
         // Initialize tracker
         let tracker = MouseTracker::new();
 
-        // Initialize hotkey system
-        let _ = HotKeySystem::new(tx).expect("Failed to initialize hotkey system");
+        // Follow monitors being plugged in, unplugged or rearranged
+        let monitor_watcher = match MonitorWatcher::new(ctx.clone()) {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                eprintln!("Monitor changes will not be noticed: {}", err);
+                None
+            }
+        };
+
+        // Pick up the path recorded in an earlier run
+        let last_path = CursorPath::load_latest(&settings.export_dir).unwrap_or_else(|err| {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                eprintln!("Failed to load the last cursor path: {}", err);
+            }
+            None
+        });
+
+        // Initialize hotkey system, another application may already own the keys
+        let mut notifier = Notifier::new();
+        let (hotkey_system, hotkey_error) = match HotKeySystem::new(tx) {
+            // Hotkeys that did not register are reported, the others work
+            Ok(hotkey_system) if !hotkey_system.failures().is_empty() => {
+                let err = hotkey_system.failures().join("\n");
+                eprintln!("{}", err);
+                notifier.notify(
+                    &settings.notifications,
+                    NotificationEvent::HotkeyError,
+                    "Hotkey unavailable",
+                    &err,
+                );
+                (Some(hotkey_system), Some(err))
+            }
+            Ok(hotkey_system) => (Some(hotkey_system), None),
+            Err(err) => {
+                eprintln!("Failed to initialize hotkey system: {}", err);
+                notifier.notify(
+                    &settings.notifications,
+                    NotificationEvent::HotkeyError,
+                    "Hotkey unavailable",
+                    &err.to_string(),
+                );
+                (None, Some(err.to_string()))
+            }
+        };
 
         // Request continuous repaints to keep UI responsive
         ctx.request_repaint_after(Duration::from_millis(config::UI_REFRESH_INTERVAL_MS));
@@ -32,8 +153,38 @@ impl MouseMinderApp {
         Self {
             tracker,
             hotkey_receiver: rx,
+            _hotkey_system: hotkey_system,
+            hotkey_error,
+            command_receiver: command_rx,
+            _instance_listener: instance_listener,
+            tray,
+            marker_overlay: MarkerOverlay::new(),
+            restore_pulse: RestorePulse::new(),
+            notifier,
+            recording_started: None,
+            session: SessionLog::new(),
+            last_path,
+            replay: None,
+            tour: TourPlayer::new(),
+            tour_error: None,
+            slot_import_path: String::new(),
+            slot_status: None,
+            monitors: monitors::current(),
+            monitor_watcher,
+            export_status: None,
+            settings,
+            settings_open: false,
+            autostart_status,
+            autostart_error: None,
+            start_hidden,
+            quitting: false,
             last_restore_time: None,
             restore_feedback_visible: false,
+            restore_adjustment: None,
+            focus: FocusTracker::new(),
+            save_skipped: None,
+            tracking_resume_at: None,
+            window_hidden: false,
         }
     }
 
@@ -41,24 +192,459 @@ impl MouseMinderApp {
     fn handle_hotkeys(&mut self) {
         while let Ok(action) = self.hotkey_receiver.try_recv() {
             match action {
-                HotKeyAction::RestorePosition => {
-                    if self.tracker.restore_position() {
-                        // Show feedback that position was restored
-                        self.last_restore_time = Some(SystemTime::now());
-                        self.restore_feedback_visible = true;
-                    }
-                }
+                HotKeyAction::RestorePosition => self.restore_position(),
+                HotKeyAction::SaveNow => self.tracker.save_now(),
+                HotKeyAction::ToggleTracking => self.toggle_tracking(),
+                HotKeyAction::AbortReplay => self.abort_replay(),
+                HotKeyAction::TourPlayPause => self.tour_play_pause(),
+                HotKeyAction::TourNext => self.tour_next(),
             }
         }
 
         // Clear restore feedback after configured duration
-        if self.restore_feedback_visible {
-            if let Some(time) = self.last_restore_time {
-                if time.elapsed().unwrap().as_millis() >= config::FEEDBACK_DURATION_MS as u128 {
-                    self.restore_feedback_visible = false;
+        if self.restore_feedback_visible
+            && let Some(time) = self.last_restore_time
+            && time.elapsed().unwrap().as_millis() >= config::FEEDBACK_DURATION_MS as u128
+        {
+            self.restore_feedback_visible = false;
+        }
+    }
+
+    // React to what the tracker did since the last frame
+    fn handle_tracker_events(&mut self) {
+        while let Some(event) = self.tracker.poll_event() {
+            self.session.record(event.clone());
+
+            match event.event {
+                TrackerEvent::PositionSaved(pos) => {
+                    self.save_skipped = None;
+                    self.notifier.notify(
+                        &self.settings.notifications,
+                        NotificationEvent::PositionSaved,
+                        "Position saved",
+                        &format!("X: {}, Y: {}", pos.x, pos.y),
+                    );
+                }
+                TrackerEvent::SaveSkipped(reason) => self.save_skipped = Some(reason.to_string()),
+                TrackerEvent::PositionRestored { position: pos, .. } => {
+                    // Show feedback that position was restored
+                    self.last_restore_time = Some(SystemTime::now());
+                    self.restore_feedback_visible = true;
+                    self.restore_adjustment = None;
+                    let (x, y) = pos.physical();
+                    self.restore_pulse.trigger(x, y);
+                }
+                TrackerEvent::RestoreAdjusted(adjustment) => {
+                    self.restore_adjustment = Some(format!(
+                        "{}: X: {}, Y: {} was off screen",
+                        adjustment.policy.label(),
+                        adjustment.from.0,
+                        adjustment.from.1
+                    ));
+                }
+                TrackerEvent::RestoreFailed(err) => self.notifier.notify(
+                    &self.settings.notifications,
+                    NotificationEvent::RestoreFailed,
+                    "Restore failed",
+                    &err.to_string(),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    // Take a new monitor layout, the position panel re-validates against it
+    fn handle_monitor_changes(&mut self) {
+        if let Some(monitors) = self.monitor_watcher.as_ref().and_then(MonitorWatcher::poll) {
+            self.monitors = monitors;
+        }
+    }
+
+    // Handle commands from the command line or forwarded by other launches
+    fn handle_commands(&mut self, ctx: &Context) {
+        while let Ok(command) = self.command_receiver.try_recv() {
+            match command {
+                Command::Show => {
+                    self.window_hidden = false;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
                 }
+                Command::Hide => self.hide_window(ctx),
+                Command::Restore => self.restore_position(),
+                Command::RestoreSlot(index) => self.restore_slot(index),
+                Command::SaveNow => self.tracker.save_now(),
+                Command::Reset => self.tracker.reset_position(),
+                Command::StartTracking => self.start_tracking(),
+                Command::StopTracking => self.stop_tracking(),
+                Command::ToggleTracking => self.toggle_tracking(),
+                Command::StartRecording => self.set_recording(true),
+                Command::StopRecording => self.set_recording(false),
+                Command::ExportSession => self.export_session(),
+                Command::ExportMarkers => self.export_markers(),
+                Command::ExportPath => self.export_path(),
+                Command::ReplayPath => self.replay_path(),
+                Command::AbortReplay => self.abort_replay(),
+                Command::TourPlayPause => self.tour_play_pause(),
+                Command::TourNext => self.tour_next(),
+                Command::TourStop => self.tour.stop(),
+                Command::Quit => {
+                    self.quitting = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            }
+        }
+    }
+
+    // Hide to the tray when there is one, otherwise just minimize
+    fn hide_window(&mut self, ctx: &Context) {
+        if self.tray.is_some() {
+            self.window_hidden = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        } else {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+        }
+    }
+
+    // Apply start minimized and close to tray
+    fn handle_window_state(&mut self, ctx: &Context) {
+        if std::mem::take(&mut self.start_hidden) {
+            self.hide_window(ctx);
+        }
+
+        let close_requested = ctx.input(|i| i.viewport().close_requested());
+        if close_requested && !self.quitting && self.settings.close_to_tray && self.tray.is_some() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.hide_window(ctx);
+        }
+    }
+
+    // Keep the tray icon and menu in sync with the tracker
+    fn sync_tray(&mut self) {
+        let saved_position = self.current_position().map(|pos| (pos.x, pos.y));
+        let slots = self
+            .settings
+            .tour
+            .slots_for(self.active_set())
+            .map(|slot| match &slot.app {
+                Some(app) => format!("{} ({})", slot.name, app),
+                None => slot.name.clone(),
+            })
+            .collect();
+        if let Some(tray) = self.tray.as_mut() {
+            tray.update(TrayState {
+                tracking: self.tracker.is_tracking(),
+                recording: self.recording_started.is_some(),
+                saved_position,
+                slots,
+            });
+        }
+    }
+
+    // Hand the save rules and where our window is to the tracking thread
+    fn sync_save_filter(&self, ctx: &Context) {
+        let pixels_per_point = ctx.pixels_per_point();
+        // egui does not report a hidden viewport, its last rect stays around
+        let own_window = ctx.input(|i| {
+            let viewport = i.viewport();
+            if self.window_hidden || viewport.minimized == Some(true) {
+                return None;
+            }
+            viewport.outer_rect.map(|rect| ExclusionZone {
+                name: config::APP_NAME.into(),
+                x: (rect.min.x * pixels_per_point).round() as i32,
+                y: (rect.min.y * pixels_per_point).round() as i32,
+                width: (rect.width() * pixels_per_point).round() as i32,
+                height: (rect.height() * pixels_per_point).round() as i32,
+            })
+        });
+        self.tracker.set_save_filter(SaveFilter {
+            rules: self.settings.save_rules.clone(),
+            own_window,
+            keyboard_policy: self
+                .settings
+                .save_rules
+                .keyboard_policy_for(self.focus.class()),
+        });
+    }
+
+    // Write or remove the login entry and refresh its status
+    fn set_autostart(&mut self, enabled: bool) {
+        let result = if enabled {
+            autostart::enable(self.settings.autostart_start_tracking)
+        } else {
+            autostart::disable()
+        };
+        self.autostart_error = result.err().map(|err| err.to_string());
+        self.autostart_status = autostart::status();
+    }
+
+    // Persist settings
+    pub fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.settings.save(storage);
+    }
+
+    // Start or stop the recording clock. A new recording starts a new session
+    // so exported offsets line up with the recording.
+    fn set_recording(&mut self, recording: bool) {
+        if !recording {
+            self.recording_started = None;
+            if let Some(path) = self.tracker.stop_path_recording() {
+                self.save_path(path);
             }
+        } else if self.recording_started.is_none() {
+            self.recording_started = Some(Instant::now());
+            self.session = SessionLog::for_recording();
+            if self.settings.record_path {
+                self.tracker.start_path_recording();
+            }
+        }
+    }
+
+    // Keep a finished cursor path and write it in the compact path format
+    fn save_path(&mut self, path: CursorPath) {
+        let dir = &self.settings.export_dir;
+        self.export_status = Some(
+            match export::write_export(dir, "path", path::FILE_EXTENSION, path.to_bytes()) {
+                Ok(file) => Ok(format!(
+                    "{} path samples saved to {}",
+                    path.samples().len(),
+                    file.display()
+                )),
+                Err(err) => Err(format!("Failed to save cursor path: {}", err)),
+            },
+        );
+        self.last_path = Some(path);
+    }
+
+    // Write the session log as CSV and JSON Lines
+    fn export_session(&mut self) {
+        let dir = &self.settings.export_dir;
+        let result = export::write_export(dir, "session", "csv", self.session.to_csv())
+            .and_then(|_| {
+                export::write_export(dir, "session", "jsonl", self.session.to_json_lines())
+            });
+
+        self.export_status = Some(match result {
+            Ok(_) => Ok(format!("Session exported to {}", dir)),
+            Err(err) => Err(format!("Failed to export session: {}", err)),
+        });
+    }
+
+    // Write the session's restores as NLE timeline markers
+    fn export_markers(&mut self) {
+        let dir = &self.settings.export_dir;
+        let result = MarkerExport::new(&self.session, &self.settings.markers).and_then(|markers| {
+            let write = |extension, contents: String| {
+                export::write_export(dir, "markers", extension, contents)
+            };
+            write("edl", markers.to_edl())
+                .and_then(|_| write("fcpxml", markers.to_fcpxml()))
+                .and_then(|_| write("csv", markers.to_resolve_csv()))
+                .map(|_| markers.len())
+                .map_err(|err| err.to_string())
+        });
+
+        self.export_status = Some(match result {
+            Ok(count) => Ok(format!("{} restore markers exported to {}", count, dir)),
+            Err(err) => Err(format!("Failed to export markers: {}", err)),
+        });
+    }
+
+    // Write the last recorded cursor path as JSON, CSV and an SVG preview
+    fn export_path(&mut self) {
+        let Some(path) = &self.last_path else {
+            self.export_status = Some(Err("No cursor path recorded yet".into()));
+            return;
+        };
+
+        let dir = &self.settings.export_dir;
+        let result = export::write_export(dir, "path", "json", path.to_json())
+            .and_then(|_| export::write_export(dir, "path", "csv", path.to_csv()))
+            .and_then(|_| export::write_export(dir, "path", "svg", path.to_svg()));
+
+        self.export_status = Some(match result {
+            Ok(_) => Ok(format!("Cursor path exported to {}", dir)),
+            Err(err) => Err(format!("Failed to export cursor path: {}", err)),
+        });
+    }
+
+    // Re-perform the last recorded cursor path, unless a replay is running
+    fn replay_path(&mut self) {
+        if self.replay.as_ref().is_some_and(|replay| replay.state().is_running()) {
+            return;
+        }
+        if let Some(path) = &self.last_path {
+            self.replay = Some(Replay::start(path, &self.settings.replay));
+        }
+    }
+
+    fn abort_replay(&mut self) {
+        if let Some(replay) = &self.replay {
+            replay.cancel();
+        }
+    }
+
+    // What the current or last replay is doing, for display
+    fn replay_status(&self) -> Option<(String, bool)> {
+        let abort_hotkey = if cfg!(target_os = "macos") {
+            "⌘+Shift+X"
+        } else {
+            "Ctrl+Shift+X"
+        };
+
+        Some(match self.replay.as_ref()?.state() {
+            ReplayState::Waiting => (
+                format!("Replay starts shortly, {} or moving the mouse aborts", abort_hotkey),
+                false,
+            ),
+            ReplayState::Playing(progress) => (
+                format!(
+                    "Replaying path {:.0}%, {} or moving the mouse aborts",
+                    progress * 100.0,
+                    abort_hotkey
+                ),
+                false,
+            ),
+            ReplayState::Finished => ("Replay finished".into(), false),
+            ReplayState::Cancelled(reason) => (format!("Replay stopped: {}", reason), true),
+            ReplayState::Failed(err) => (format!("Replay failed: {}", err), true),
+        })
+    }
+
+    // Start, pause or resume the tour
+    fn tour_play_pause(&mut self) {
+        if !self.tour.is_running() {
+            self.tour_error = None;
+        }
+        let stop = self.tour.play_pause(&self.settings.tour);
+        self.visit_tour_stop(stop);
+    }
+
+    // Skip to the next tour stop
+    fn tour_next(&mut self) {
+        if !self.tour.is_running() {
+            self.tour_error = None;
         }
+        let stop = self.tour.advance(&self.settings.tour);
+        self.visit_tour_stop(stop);
+    }
+
+    // Write the tour slots as monitor fractions
+    fn export_slots(&mut self) {
+        let (json, count) = self.settings.tour.export_slots(&self.monitors);
+        self.slot_status = Some(
+            match export::write_export(&self.settings.export_dir, "slots", "json", json) {
+                Ok(file) => Ok(format!("{} slots exported to {}", count, file.display())),
+                Err(err) => Err(format!("Failed to export slots: {}", err)),
+            },
+        );
+    }
+
+    // Add the slots from an exported slot set
+    fn import_slots(&mut self) {
+        let result = std::fs::read_to_string(self.slot_import_path.trim())
+            .map_err(|err| err.to_string())
+            .and_then(|json| self.settings.tour.import_slots(&json, &self.monitors));
+        self.slot_status = Some(match result {
+            Ok(count) => Ok(format!("{} slots imported", count)),
+            Err(err) => Err(format!("Failed to import slots: {}", err)),
+        });
+    }
+
+    // Move the cursor to a tour stop's slot through the regular restore path
+    fn visit_tour_stop(&mut self, index: Option<usize>) {
+        let Some(stop) = index.and_then(|index| self.settings.tour.stops.get(index)) else {
+            return;
+        };
+        let Some(slot) = self.settings.tour.slot_in_set(&stop.slot, self.active_set()) else {
+            self.tour_error = Some(format!("No slot named \"{}\" in the active set", stop.slot));
+            return;
+        };
+
+        if self
+            .tracker
+            .restore_to_slot(slot, &self.settings.restore)
+            .is_ok()
+            && stop.click
+            && let Err(err) = self.tracker.click()
+        {
+            self.tour_error = Some(format!("Could not click: {}", err));
+        }
+    }
+
+    fn start_tracking(&mut self) {
+        self.tracking_resume_at = None;
+        self.tracker.start_tracking();
+    }
+
+    fn stop_tracking(&mut self) {
+        self.tracking_resume_at = None;
+        self.tracker.stop_tracking();
+    }
+
+    // Stop tracking and start again after a while
+    fn pause_tracking(&mut self, minutes: u32) {
+        self.tracker.stop_tracking();
+        self.tracking_resume_at =
+            Some(Instant::now() + Duration::from_secs(u64::from(minutes) * 60));
+    }
+
+    // Stop or start tracking, pausing for the configured time when stopping
+    fn toggle_tracking(&mut self) {
+        if !self.tracker.is_tracking() {
+            self.start_tracking();
+        } else if self.settings.pause_minutes > 0 {
+            self.pause_tracking(self.settings.pause_minutes);
+        } else {
+            self.stop_tracking();
+        }
+    }
+
+    // Start a paused tracker again once its time is up
+    fn resume_tracking_when_due(&mut self) {
+        if self
+            .tracking_resume_at
+            .is_some_and(|resume_at| Instant::now() >= resume_at)
+        {
+            self.start_tracking();
+        }
+    }
+
+    // Restore the cursor, feedback follows from the tracker events
+    fn restore_position(&mut self) {
+        // Hotkeys may come right after switching applications
+        self.focus.refresh();
+        let app = self.active_set().map(str::to_owned);
+        let _ = self
+            .tracker
+            .restore_position(app.as_deref(), &self.settings.restore);
+    }
+
+    // Restore to a slot picked from the tray or the command line, by its
+    // index among the slots of the active set
+    fn restore_slot(&mut self, index: usize) {
+        self.focus.refresh();
+        let Some(slot) = self.settings.tour.slots_for(self.active_set()).nth(index) else {
+            self.tour_error = Some(format!("No slot number {} in the active set", index + 1));
+            return;
+        };
+        let _ = self.tracker.restore_to_slot(slot, &self.settings.restore);
+    }
+
+    // Window class of the application whose positions restores use, None
+    // when positions are not kept per application
+    fn active_set(&self) -> Option<&str> {
+        if self.settings.restore.per_app_positions {
+            self.focus.class()
+        } else {
+            None
+        }
+    }
+
+    // The position a restore would go to now
+    fn current_position(&self) -> Option<SavedPosition> {
+        self.tracker.get_saved_position_for(self.active_set())
     }
 
     // Format time for display
@@ -76,9 +662,16 @@ impl MouseMinderApp {
     // Create color-coded status indicator
     fn status_indicator(&self, ui: &mut Ui) {
         let (status_text, status_color) = if self.tracker.is_tracking() {
-            ("TRACKING", Color32::from_rgb(76, 175, 80))
+            ("TRACKING".to_string(), Color32::from_rgb(76, 175, 80))
+        } else if let Some(resume_at) = self.tracking_resume_at {
+            // Time left until tracking resumes by itself
+            let secs = resume_at.saturating_duration_since(Instant::now()).as_secs();
+            (
+                format!("PAUSED {}:{:02}", secs / 60, secs % 60),
+                Color32::from_rgb(255, 152, 0),
+            )
         } else {
-            ("PAUSED", Color32::from_rgb(255, 152, 0))
+            ("PAUSED".to_string(), Color32::from_rgb(255, 152, 0))
         };
 
         let status_bg = if self.tracker.is_tracking() {
@@ -87,7 +680,7 @@ impl MouseMinderApp {
             Color32::from_rgb(70, 50, 20) // Darker orange for dark mode
         };
 
-        ui.horizontal_centered(|ui| {
+        ui.vertical_centered(|ui| {
             // Create a layout that only occupies the space needed
             let circle_radius = 8.0;
             let padding = 12;
@@ -128,12 +721,41 @@ impl MouseMinderApp {
 
     // Update and render the UI
     pub fn update(&mut self, ctx: &Context) {
-        // Handle any pending hotkey actions
+        // Handle any pending commands and hotkey actions
+        self.focus.poll(ctx.input(|input| input.focused));
+        self.handle_commands(ctx);
         self.handle_hotkeys();
+        self.handle_tracker_events();
+        self.handle_monitor_changes();
+        self.handle_window_state(ctx);
+        self.resume_tracking_when_due();
+        self.sync_tray();
+        self.sync_save_filter(ctx);
+
+        let saved_position = self.current_position();
+        self.marker_overlay
+            .show(ctx, &self.settings.overlay, saved_position.as_slice());
+        self.restore_pulse.show(
+            ctx,
+            &self.settings.pulse,
+            self.recording_started.is_some(),
+        );
+
+        // Step the tour once the dwell time at the current stop is over
+        let stop = self.tour.tick(&self.settings.tour);
+        self.visit_tour_stop(stop);
+        if let Some(remaining) = self.tour.time_to_next_stop() {
+            ctx.request_repaint_after(remaining);
+        }
 
         // Request a repaint to keep the UI responsive
         ctx.request_repaint_after(Duration::from_millis(config::UI_REFRESH_INTERVAL_MS));
 
+        if self.settings_open {
+            self.settings_view(ctx);
+            return;
+        }
+
         // Custom colors
         let app_bg = Color32::from_rgb(30, 30, 35);
         let panel_bg = Color32::from_rgb(45, 45, 50);
@@ -143,223 +765,455 @@ impl MouseMinderApp {
         egui::CentralPanel::default()
             .frame(egui::Frame::default().fill(app_bg))
             .show(ctx, |ui| {
-                ui.vertical_centered(|ui| {
-                    // Main content area
-                    egui::Frame::new()
-                    .inner_margin(egui::Margin::symmetric(20,0))
-                    .show(ui, |ui| {
-                                ui.vertical_centered(|ui| {
-                                    // Status indicator
-                                    ui.add_space(-550.0);
-                                    self.status_indicator(ui);
-                                    ui.add_space(16.0);
-
-                                    // Position info
-                                    egui::Frame::new()
-                                        .fill(panel_bg)
-                                        .corner_radius(CornerRadius::same(8))
-                                        .stroke(Stroke::new(1.0, Color32::from_rgb(230, 230, 240)))
-                                        .shadow(egui::epaint::Shadow {
-                                            offset: [0, 2],
-                                            blur: 4,
-                                            spread: 0,
-                                            color: Color32::from_rgb(0, 0, 0).linear_multiply(0.1),
-                                        })
-                                        .inner_margin(egui::Margin::same(16))
-                                        .show(ui, |ui| {
-                                            ui.vertical_centered(|ui| {
-                                                ui.heading(
-                                                    RichText::new("Last Saved Position")
-                                                        .color(text_color)
-                                                        .size(18.0),
-                                                );
-                                                ui.add_space(10.0);
-
-                                                if let Some(pos) = self.tracker.get_saved_position() {
-                                                    // Coordinates
-                                                    let coords_text = format!("X: {}, Y: {}", pos.x, pos.y);
-                                                    ui.label(
-                                                        RichText::new(coords_text)
-                                                            .size(20.0)
+                // Scroll when the content is taller than the window
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        // Main content area
+                        egui::Frame::new()
+                        .inner_margin(egui::Margin::symmetric(20,0))
+                        .show(ui, |ui| {
+                                    ui.vertical_centered(|ui| {
+                                        // Status indicator
+                                        ui.add_space(20.0);
+                                        self.status_indicator(ui);
+                                        ui.add_space(16.0);
+
+                                        // Position info
+                                        egui::Frame::new()
+                                            .fill(panel_bg)
+                                            .corner_radius(CornerRadius::same(8))
+                                            .stroke(Stroke::new(1.0, Color32::from_rgb(230, 230, 240)))
+                                            .shadow(egui::epaint::Shadow {
+                                                offset: [0, 2],
+                                                blur: 4,
+                                                spread: 0,
+                                                color: Color32::from_rgb(0, 0, 0).linear_multiply(0.1),
+                                            })
+                                            .inner_margin(egui::Margin::same(16))
+                                            .show(ui, |ui| {
+                                                ui.vertical_centered(|ui| {
+                                                    ui.heading(
+                                                        RichText::new("Last Saved Position")
                                                             .color(text_color)
+                                                            .size(18.0),
                                                     );
+                                                    // Which application's position this is
+                                                    if self.settings.restore.per_app_positions {
+                                                        ui.label(
+                                                            RichText::new(format!(
+                                                                "Set: {}",
+                                                                self.active_set().unwrap_or("No application focused yet")
+                                                            ))
+                                                            .color(Color32::from_rgb(150, 150, 170))
+                                                            .size(13.0),
+                                                        );
+                                                    }
+                                                    ui.add_space(10.0);
 
-                                                    ui.add_space(4.0);
+                                                    if let Some(pos) = self.current_position() {
+                                                        // Coordinates
+                                                        let coords_text = pos.describe(&self.settings.restore);
+                                                        ui.label(
+                                                            RichText::new(coords_text)
+                                                                .size(20.0)
+                                                                .color(text_color)
+                                                        );
 
-                                                    // Timestamp
-                                                    ui.label(
-                                                        RichText::new(format!(
-                                                            "Saved at: {}",
-                                                            Self::format_time(pos.timestamp)
-                                                        ))
-                                                        .color(Color32::from_rgb(120, 120, 140))
-                                                        .size(14.0),
-                                                    );
-                                                } else {
-                                                    ui.label(
-                                                        RichText::new("No position saved yet")
-                                                            .italics()
+                                                        // Both coordinate spaces, they differ under scaling
+                                                        let (physical_x, physical_y) = pos.physical();
+                                                        let (logical_x, logical_y) = pos.logical();
+                                                        ui.label(
+                                                            RichText::new(format!(
+                                                                "Physical {}, {} · Logical {}, {} @ {}x",
+                                                                physical_x,
+                                                                physical_y,
+                                                                logical_x,
+                                                                logical_y,
+                                                                pos.scale_factor
+                                                            ))
                                                             .color(Color32::from_rgb(150, 150, 170))
-                                                            .size(16.0),
-                                                    );
-                                                }
+                                                            .size(13.0),
+                                                        );
+
+                                                        // Monitor it was saved on
+                                                        if let Some(monitor) = &pos.monitor {
+                                                            ui.label(
+                                                                RichText::new(format!(
+                                                                    "{} at {}, {}",
+                                                                    monitor.monitor.label(),
+                                                                    monitor.x,
+                                                                    monitor.y
+                                                                ))
+                                                                .color(Color32::from_rgb(150, 150, 170))
+                                                                .size(13.0),
+                                                            );
+                                                        }
+
+                                                        // Buttons held when it was saved
+                                                        if pos.buttons != 0 {
+                                                            ui.label(
+                                                                RichText::new(format!(
+                                                                    "Saved with {} held",
+                                                                    path::button_names(pos.buttons)
+                                                                ))
+                                                                .color(Color32::from_rgb(150, 150, 170))
+                                                                .size(13.0),
+                                                            );
+                                                        }
+
+                                                        // Window it was saved over
+                                                        if let Some(anchor) = &pos.window {
+                                                            ui.label(
+                                                                RichText::new(format!(
+                                                                    "In {} at {}, {}",
+                                                                    anchor.window.label(),
+                                                                    anchor.x,
+                                                                    anchor.y
+                                                                ))
+                                                                .color(Color32::from_rgb(150, 150, 170))
+                                                                .size(13.0),
+                                                            );
+                                                        }
+
+                                                        // Warn instead of silently landing somewhere else
+                                                        if pos.is_on_missing_monitor(&self.monitors) {
+                                                            let warning = match pos.target(&self.monitors, &self.settings.restore) {
+                                                                Ok(((x, y), _)) => format!(
+                                                                    "⚠ Monitor disconnected, restoring to X: {}, Y: {}",
+                                                                    x, y
+                                                                ),
+                                                                Err(err) => format!("⚠ Monitor disconnected: {}", err),
+                                                            };
+                                                            ui.label(
+                                                                RichText::new(warning)
+                                                                .color(Color32::from_rgb(255, 152, 0))
+                                                                .size(13.0),
+                                                            );
+                                                        }
+
+                                                        ui.add_space(4.0);
+
+                                                        // Timestamp
+                                                        ui.label(
+                                                            RichText::new(format!(
+                                                                "Saved at: {}",
+                                                                Self::format_time(pos.timestamp)
+                                                            ))
+                                                            .color(Color32::from_rgb(120, 120, 140))
+                                                            .size(14.0),
+                                                        );
+                                                    } else {
+                                                        ui.label(
+                                                            RichText::new("No position saved yet")
+                                                                .italics()
+                                                                .color(Color32::from_rgb(150, 150, 170))
+                                                                .size(16.0),
+                                                        );
+                                                    }
+
+                                                    // Tour slots the layout change left without their monitor
+                                                    let missing_slots = self
+                                                        .settings
+                                                        .tour
+                                                        .slots
+                                                        .iter()
+                                                        .filter(|slot| slot.position.is_on_missing_monitor(&self.monitors))
+                                                        .count();
+                                                    if missing_slots > 0 {
+                                                        ui.label(
+                                                            RichText::new(format!(
+                                                                "⚠ {} tour slots are on a disconnected monitor",
+                                                                missing_slots
+                                                            ))
+                                                            .color(Color32::from_rgb(255, 152, 0))
+                                                            .size(13.0),
+                                                        );
+                                                    }
+
+                                                    // Why the cursor's resting spot was not saved
+                                                    if let Some(reason) = &self.save_skipped {
+                                                        ui.label(
+                                                            RichText::new(format!("Save skipped: {}", reason))
+                                                                .color(Color32::from_rgb(150, 150, 170))
+                                                                .size(13.0),
+                                                        );
+                                                    }
+                                                });
                                             });
-                                        });
 
-                                    // Restore feedback
-                                    if self.restore_feedback_visible {
-                                        ui.add_space(16.0);
+                                        // Restore feedback
+                                        if self.restore_feedback_visible {
+                                            ui.add_space(16.0);
+                                            egui::Frame::new()
+                                                .fill(Color32::from_rgb(232, 245, 233))
+                                                .corner_radius(CornerRadius::same(8))
+                                                .inner_margin(egui::Margin::same(10))
+                                                .show(ui, |ui| {
+                                                    ui.vertical_centered(|ui| {
+                                                        ui.label(
+                                                            RichText::new("Position Restored!")
+                                                                .color(Color32::from_rgb(46, 125, 50))
+                                                                .size(16.0)
+                                                                .strong(),
+                                                        );
+
+                                                        // Which bounds policy moved the target on screen
+                                                        if let Some(adjustment) = &self.restore_adjustment {
+                                                            ui.label(
+                                                                RichText::new(adjustment)
+                                                                    .color(Color32::from_rgb(46, 125, 50))
+                                                                    .size(12.0),
+                                                            );
+                                                        }
+                                                    });
+                                                });
+                                        }
+
+                                        // Replay progress, with how to get out of it
+                                        if let Some((status, warning)) = self.replay_status()
+                                            && self.replay.as_ref().is_some_and(|replay| replay.state().is_running())
+                                        {
+                                            ui.add_space(16.0);
+                                            let color = if warning {
+                                                Color32::from_rgb(255, 152, 0)
+                                            } else {
+                                                text_color
+                                            };
+                                            ui.label(RichText::new(status).color(color).size(14.0));
+                                        }
+
+                                        // Tour progress
+                                        if let Some(status) = self.tour.status(&self.settings.tour) {
+                                            ui.add_space(16.0);
+                                            ui.label(RichText::new(status).color(text_color).size(14.0));
+                                        }
+
+                                        ui.add_space(24.0);
+
+                                        // Control buttons - centered
                                         egui::Frame::new()
-                                            .fill(Color32::from_rgb(232, 245, 233))
-                                            .corner_radius(CornerRadius::same(8))
-                                            .inner_margin(egui::Margin::same(10))
                                             .show(ui, |ui| {
                                                 ui.vertical_centered(|ui| {
-                                                    ui.label(
-                                                        RichText::new("Position Restored!")
-                                                            .color(Color32::from_rgb(46, 125, 50))
-                                                            .size(16.0)
-                                                            .strong(),
-                                                    );
-                                                });
-                                            });
-                                    }
-
-                                    ui.add_space(24.0);
-
-                                    // Control buttons - centered
-                                    egui::Frame::new()
-                                        .show(ui, |ui| {
-                                            ui.vertical_centered(|ui| {
-                                                // First row - start/stop button
-                                                let track_button_text;
-                                                let track_button_color;
-                                                let track_button_text_color;
-
-                                                if self.tracker.is_tracking() {
-                                                    track_button_text = "⏹ Stop Tracking";
-                                                    track_button_color = Color32::from_rgb(239, 83, 80);
-                                                    track_button_text_color = Color32::WHITE;
-                                                } else {
-                                                    track_button_text = "▶ Start Tracking";
-                                                    track_button_color = Color32::from_rgb(76, 175, 80);
-                                                    track_button_text_color = Color32::LIGHT_GRAY;
-                                                }
-
-                                                let track_button = egui::Button::new(
-                                                    RichText::new(track_button_text)
-                                                        .color(track_button_text_color)
-                                                        .size(16.0),
-                                                )
-                                                .fill(track_button_color)
-                                                .corner_radius(CornerRadius::same(6))
-                                                .min_size(egui::Vec2::new(180.0, 40.0));
-
-                                                if self.tracker.is_tracking() {
-                                                    if ui.add(track_button).clicked() {
-                                                        self.tracker.stop_tracking();
-                                                    }
-                                                } else if ui.add(track_button).clicked() {
-                                                    self.tracker.start_tracking();
-                                                }
+                                                    // First row - start/stop button
+                                                    let track_button_text;
+                                                    let track_button_color;
+                                                    let track_button_text_color;
 
-                                                ui.add_space(12.0);
+                                                    if self.tracker.is_tracking() {
+                                                        track_button_text = "⏹ Stop Tracking";
+                                                        track_button_color = Color32::from_rgb(239, 83, 80);
+                                                        track_button_text_color = Color32::WHITE;
+                                                    } else {
+                                                        track_button_text = "▶ Start Tracking";
+                                                        track_button_color = Color32::from_rgb(76, 175, 80);
+                                                        track_button_text_color = Color32::LIGHT_GRAY;
+                                                    }
 
-                                                // Second row - reset
-                                                ui.vertical_centered(|ui| {
-                                                    let button_height = 36.0;
-                                                    let button_width = 150.0;
-                                                    
-                                                    // Reset button
-                                                    let reset_button = egui::Button::new(
-                                                        RichText::new("🗑 Reset Position")
-                                                            .color(Color32::GRAY)
-                                                            .size(14.0),
+                                                    let track_button = egui::Button::new(
+                                                        RichText::new(track_button_text)
+                                                            .color(track_button_text_color)
+                                                            .size(16.0),
                                                     )
-                                                    .min_size(egui::Vec2::new(button_width, button_height))
+                                                    .fill(track_button_color)
                                                     .corner_radius(CornerRadius::same(6))
-                                                    .fill(Color32::from_rgb(47, 54, 64));
+                                                    .min_size(egui::Vec2::new(180.0, 40.0));
 
-                                                    if ui.add(reset_button).clicked() {
-                                                        self.tracker.reset_position();
+                                                    if self.tracker.is_tracking() {
+                                                        if ui.add(track_button).clicked() {
+                                                            self.stop_tracking();
+                                                        }
+
+                                                        // Pause with auto-resume
+                                                        let minutes = self.settings.pause_minutes;
+                                                        if minutes > 0 && ui.button(format!("⏸ Pause for {} min", minutes)).clicked() {
+                                                            self.pause_tracking(minutes);
+                                                        }
+                                                    } else if ui.add(track_button).clicked() {
+                                                        self.start_tracking();
                                                     }
 
                                                     ui.add_space(12.0);
+
+                                                    // Second row - save and reset
+                                                    ui.vertical_centered(|ui| {
+                                                        let button_height = 36.0;
+                                                        let button_width = 150.0;
+
+                                                        // Save button, works whether tracking or not
+                                                        let save_button = egui::Button::new(
+                                                            RichText::new("📌 Save Current Position")
+                                                                .color(Color32::LIGHT_GRAY)
+                                                                .size(14.0),
+                                                        )
+                                                        .min_size(egui::Vec2::new(button_width, button_height))
+                                                        .corner_radius(CornerRadius::same(6))
+                                                        .fill(Color32::from_rgb(47, 54, 64));
+
+                                                        if ui.add(save_button).clicked() {
+                                                            self.tracker.save_now();
+                                                        }
+
+                                                        ui.add_space(8.0);
+                                                    
+                                                        // Reset button
+                                                        let reset_button = egui::Button::new(
+                                                            RichText::new("🗑 Reset Position")
+                                                                .color(Color32::GRAY)
+                                                                .size(14.0),
+                                                        )
+                                                        .min_size(egui::Vec2::new(button_width, button_height))
+                                                        .corner_radius(CornerRadius::same(6))
+                                                        .fill(Color32::from_rgb(47, 54, 64));
+
+                                                        if ui.add(reset_button).clicked() {
+                                                            self.tracker.reset_position();
+                                                        }
+
+                                                        ui.add_space(12.0);
+                                                    });
                                                 });
                                             });
-                                        });
 
-                                    ui.add_space(24.0);
+                                        ui.add_space(24.0);
 
-                                    // Instructions
-                                    ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-                                        egui::Frame::new()
-                                            .fill(Color32::from_rgb(47, 54, 64))
-                                            .corner_radius(CornerRadius::same(8))
-                                            .inner_margin(egui::Margin::same(16))
-                                            .show(ui, |ui| {
-                                                ui.vertical_centered(|ui| {
-                                                    ui.heading(
-                                                        RichText::new("Instructions")
-                                                            .color(Color32::from_rgb(220, 221, 225))
-                                                            .size(16.0),
-                                                    );
-                                                    ui.add_space(8.0);
-
-                                                    // Add a subtle separator
-                                                    let separator_stroke =
-                                                        Stroke::new(1.0, Color32::from_rgb(220, 220, 230));
-                                                    let y = ui.cursor().min.y;
-                                                    let rect = ui.max_rect();
-                                                    let line_start = egui::Pos2::new(rect.min.x, y);
-                                                    let line_end = egui::Pos2::new(rect.max.x, y);
-                                                    ui.painter().line_segment([line_start, line_end], separator_stroke);
-                                                    ui.add_space(10.0);
+                                        // Instructions
+                                        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+                                            egui::Frame::new()
+                                                .fill(Color32::from_rgb(47, 54, 64))
+                                                .corner_radius(CornerRadius::same(8))
+                                                .inner_margin(egui::Margin::same(16))
+                                                .show(ui, |ui| {
+                                                    ui.vertical_centered(|ui| {
+                                                        ui.heading(
+                                                            RichText::new("Instructions")
+                                                                .color(Color32::from_rgb(220, 221, 225))
+                                                                .size(16.0),
+                                                        );
+                                                        ui.add_space(8.0);
 
-                                                    ui.label(
-                                                        RichText::new("• Mouse position is saved after 2 seconds of inactivity")
-                                                            .color(text_color)
-                                                            .size(14.0),
-                                                    );
+                                                        // Add a subtle separator
+                                                        let separator_stroke =
+                                                            Stroke::new(1.0, Color32::from_rgb(220, 220, 230));
+                                                        let y = ui.cursor().min.y;
+                                                        let rect = ui.max_rect();
+                                                        let line_start = egui::Pos2::new(rect.min.x, y);
+                                                        let line_end = egui::Pos2::new(rect.max.x, y);
+                                                        ui.painter().line_segment([line_start, line_end], separator_stroke);
+                                                        ui.add_space(10.0);
 
-                                                    let hotkey_text = if cfg!(target_os = "macos") {
-                                                        "• Press ⌘+Shift+R to restore mouse position"
-                                                    } else {
-                                                        "• Press Ctrl+Shift+R to restore mouse position"
-                                                    };
+                                                        ui.label(
+                                                            RichText::new("• Mouse position is saved after 2 seconds of inactivity")
+                                                                .color(text_color)
+                                                                .size(14.0),
+                                                        );
 
-                                                    ui.label(
-                                                        RichText::new(hotkey_text)
-                                                            .color(text_color)
-                                                            .size(14.0),
-                                                    );
+                                                        let hotkey_text = if cfg!(target_os = "macos") {
+                                                            "• Press ⌘+Shift+R to restore mouse position"
+                                                        } else {
+                                                            "• Press Ctrl+Shift+R to restore mouse position"
+                                                        };
 
+                                                        ui.label(
+                                                            RichText::new(hotkey_text)
+                                                                .color(text_color)
+                                                                .size(14.0),
+                                                        );
+
+                                                        let toggle_hotkey_text = if cfg!(target_os = "macos") {
+                                                            "• Press ⌘+Shift+K to start or stop tracking"
+                                                        } else {
+                                                            "• Press Ctrl+Shift+K to start or stop tracking"
+                                                        };
+
+                                                        ui.label(
+                                                            RichText::new(toggle_hotkey_text)
+                                                                .color(text_color)
+                                                                .size(14.0),
+                                                        );
+
+                                                        let save_hotkey_text = if cfg!(target_os = "macos") {
+                                                            "• Press ⌘+Shift+M to save the position right away"
+                                                        } else {
+                                                            "• Press Ctrl+Shift+M to save the position right away"
+                                                        };
+
+                                                        ui.label(
+                                                            RichText::new(save_hotkey_text)
+                                                                .color(text_color)
+                                                                .size(14.0),
+                                                        );
+
+                                                        // Explain which hotkeys will not work
+                                                        for err in self.hotkey_error.iter().flat_map(|err| err.lines()) {
+                                                            ui.label(
+                                                                RichText::new(format!("• {}", err))
+                                                                    .color(Color32::from_rgb(255, 152, 0))
+                                                                    .size(14.0),
+                                                            );
+                                                        }
+
+                                                        ui.label(
+                                                            RichText::new("• Click 'Start Tracking' to begin watching for idle positions")
+                                                                .color(text_color)
+                                                                .size(14.0),
+                                                        );
+                                                    });
+                                            });
+                                        });
+
+                                        // Footer with app name and version
+                                        ui.add_space(16.0);
+                                        egui::Frame::new()
+                                            .fill(Color32::from_rgb(25, 25, 30))
+                                            .inner_margin(egui::Margin::same(8))
+                                            .show(ui, |ui| {
+                                                ui.vertical_centered(|ui| {
                                                     ui.label(
-                                                        RichText::new("• Click 'Start Tracking' to begin watching for idle positions")
-                                                            .color(text_color)
-                                                            .size(14.0),
+                                                        RichText::new(format!("{} v{}", config::APP_NAME, config::APP_VERSION))
+                                                            .size(12.0)
+                                                            .color(Color32::from_rgb(150, 150, 160)),
                                                     );
                                                 });
-                                        });
-                                    });
-
-                                    // Footer with app name and version
-                                    ui.add_space(16.0);
-                                    egui::Frame::new()
-                                        .fill(Color32::from_rgb(25, 25, 30))
-                                        .inner_margin(egui::Margin::same(8))
-                                        .show(ui, |ui| {
-                                            ui.vertical_centered(|ui| {
-                                                ui.label(
-                                                    RichText::new(format!("{} v{}", config::APP_NAME, config::APP_VERSION))
-                                                        .size(12.0)
-                                                        .color(Color32::from_rgb(150, 150, 160)),
-                                                );
                                             });
-                                        });
-                                });
+                                    });
+                        });
                     });
                 });
             });
+
+        // Recording toggle in the top left corner
+        egui::Area::new(egui::Id::new("recording_button"))
+            .anchor(egui::Align2::LEFT_TOP, Vec2::new(8.0, 8.0))
+            .show(ctx, |ui| {
+                let recording = self.recording_started.is_some();
+                let (text, color, hover) = if recording {
+                    ("⏺ REC", Color32::from_rgb(239, 83, 80), "Stop recording")
+                } else {
+                    ("⏺", Color32::GRAY, "Start recording")
+                };
+                let recording_button =
+                    egui::Button::new(RichText::new(text).color(color).size(16.0))
+                        .corner_radius(CornerRadius::same(6))
+                        .fill(Color32::from_rgb(47, 54, 64));
+
+                if ui.add(recording_button).on_hover_text(hover).clicked() {
+                    self.set_recording(!recording);
+                }
+            });
+
+        // Settings button in the top right corner
+        egui::Area::new(egui::Id::new("settings_button"))
+            .anchor(egui::Align2::RIGHT_TOP, Vec2::new(-8.0, 8.0))
+            .show(ctx, |ui| {
+                let settings_button = egui::Button::new(
+                    RichText::new("⚙").color(Color32::GRAY).size(16.0),
+                )
+                .corner_radius(CornerRadius::same(6))
+                .fill(Color32::from_rgb(47, 54, 64));
+
+                if ui.add(settings_button).on_hover_text("Settings").clicked() {
+                    self.settings_open = true;
+                }
+            });
     }
 }
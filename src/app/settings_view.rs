@@ -0,0 +1,816 @@
+use egui::{Color32, Context, CornerRadius, RichText, Ui};
+
+use super::MouseMinderApp;
+use crate::autostart::AutostartStatus;
+use crate::markers::FrameRate;
+use crate::monitors::CoordinateSpace;
+use crate::overlay::{MarkerStyle, OverlayMode, OverlayModifier};
+use crate::path::{BUTTON_LEFT, BUTTON_MIDDLE, BUTTON_RIGHT};
+use crate::rules::{ExclusionZone, KeyboardPolicy, KeyboardProfile, SaveTrigger, WindowRule};
+use crate::tour::{Slot, TourStop};
+use crate::tracker::BoundsPolicy;
+
+const TEXT_COLOR: Color32 = Color32::from_rgb(220, 220, 230);
+const HINT_COLOR: Color32 = Color32::from_rgb(150, 150, 170);
+const WARNING_COLOR: Color32 = Color32::from_rgb(255, 152, 0);
+
+impl MouseMinderApp {
+    // Render the settings page in place of the main view
+    pub(super) fn settings_view(&mut self, ctx: &Context) {
+        egui::CentralPanel::default()
+            .frame(
+                egui::Frame::default()
+                    .fill(Color32::from_rgb(30, 30, 35))
+                    .inner_margin(egui::Margin::same(16)),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("← Back").clicked() {
+                        self.settings_open = false;
+                    }
+                    ui.heading(RichText::new("Settings").color(TEXT_COLOR).size(18.0));
+                });
+                ui.add_space(12.0);
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    section(ui, "Session", |ui| self.session_settings(ui));
+                    section(ui, "Positions", |ui| self.position_settings(ui));
+                    section(ui, "Tracking", |ui| self.tracking_settings(ui));
+                    section(ui, "Save Rules", |ui| self.save_rule_settings(ui));
+                    section(ui, "Tour", |ui| self.tour_settings(ui));
+                    section(ui, "Tray", |ui| self.tray_settings(ui));
+                    section(ui, "Startup", |ui| self.startup_settings(ui));
+                    section(ui, "Marker Overlay", |ui| self.overlay_settings(ui));
+                    section(ui, "Restore Pulse", |ui| self.pulse_settings(ui));
+                    section(ui, "Notifications", |ui| self.notification_settings(ui));
+                });
+            });
+    }
+
+    fn session_settings(&mut self, ui: &mut Ui) {
+        hint(
+            ui,
+            &format!(
+                "{} events since {}",
+                self.session.entries().len(),
+                Self::format_time(self.session.started_at())
+            ),
+        );
+
+        if ui.button("Export session").clicked() {
+            self.export_session();
+        }
+
+        // Restores become markers on the recording timeline
+        let markers = &mut self.settings.markers;
+        combo(
+            ui,
+            "Frame rate",
+            &mut markers.frame_rate,
+            &FrameRate::ALL,
+            FrameRate::label,
+        );
+        ui.horizontal(|ui| {
+            ui.label("Start timecode");
+            ui.text_edit_singleline(&mut markers.start_timecode);
+        });
+        if markers.start_frame().is_none() {
+            ui.label(
+                RichText::new("Use non-drop-frame HH:MM:SS:FF")
+                    .color(WARNING_COLOR)
+                    .size(12.0),
+            );
+        }
+        if ui.button("Export restore markers").clicked() {
+            self.export_markers();
+        }
+        hint(ui, "Writes CMX3600 EDL, FCPXML 1.10 and Resolve marker CSV");
+
+        // The full trajectory, for cursor smoothing and zoom effects in post
+        ui.checkbox(
+            &mut self.settings.record_path,
+            "Record the cursor path while recording",
+        );
+        if let Some(path) = &self.last_path {
+            hint(
+                ui,
+                &format!(
+                    "Last path: {:.1} s, {} samples, {} clicks",
+                    path.duration().as_secs_f32(),
+                    path.samples().len(),
+                    path.click_count()
+                ),
+            );
+        }
+        if ui
+            .add_enabled(
+                self.last_path.is_some(),
+                egui::Button::new("Export cursor path"),
+            )
+            .clicked()
+        {
+            self.export_path();
+        }
+        hint(
+            ui,
+            "Writes JSON, CSV and an SVG preview with a dwell heatmap",
+        );
+
+        // Re-perform the last path for a re-take
+        let replay = &mut self.settings.replay;
+        ui.add(egui::Slider::new(&mut replay.speed, 0.25..=4.0).text("× speed"));
+        ui.add(egui::Slider::new(&mut replay.smoothing, 0..=10).text("smoothing"));
+        ui.checkbox(&mut replay.replay_clicks, "Replay clicks");
+        ui.add(egui::Slider::new(&mut replay.start_delay_secs, 0.0..=10.0).text("seconds delay"));
+        ui.horizontal(|ui| {
+            let running = self
+                .replay
+                .as_ref()
+                .is_some_and(|replay| replay.state().is_running());
+            if ui
+                .add_enabled(
+                    self.last_path.is_some() && !running,
+                    egui::Button::new("Replay cursor path"),
+                )
+                .clicked()
+            {
+                self.replay_path();
+            }
+            if ui
+                .add_enabled(running, egui::Button::new("Abort"))
+                .clicked()
+            {
+                self.abort_replay();
+            }
+        });
+        match self.replay_status() {
+            Some((status, true)) => {
+                ui.label(RichText::new(status).color(WARNING_COLOR).size(12.0));
+            }
+            Some((status, false)) => hint(ui, &status),
+            None => {}
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Export folder");
+            ui.text_edit_singleline(&mut self.settings.export_dir);
+        });
+
+        match &self.export_status {
+            Some(Ok(message)) => hint(ui, message),
+            Some(Err(message)) => {
+                ui.label(RichText::new(message).color(WARNING_COLOR).size(12.0));
+            }
+            None => {}
+        }
+    }
+
+    fn tracking_settings(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Pause for");
+            ui.add(
+                egui::DragValue::new(&mut self.settings.pause_minutes)
+                    .range(0..=240)
+                    .suffix(" min"),
+            );
+        });
+        let hotkey = if cfg!(target_os = "macos") {
+            "⌘+Shift+K"
+        } else {
+            "Ctrl+Shift+K"
+        };
+        hint(
+            ui,
+            &format!(
+                "{} stops tracking, or pauses it for this long and resumes by itself. \
+                 0 stops it until started again.",
+                hotkey
+            ),
+        );
+    }
+
+    fn save_rule_settings(&mut self, ui: &mut Ui) {
+        let rules = &mut self.settings.save_rules;
+        combo(
+            ui,
+            "Save when",
+            &mut rules.trigger,
+            &SaveTrigger::ALL,
+            SaveTrigger::label,
+        );
+        if rules.trigger.on_click() {
+            ui.horizontal(|ui| {
+                ui.label("Clicks of");
+                for (button, name) in [
+                    (BUTTON_LEFT, "Left"),
+                    (BUTTON_RIGHT, "Right"),
+                    (BUTTON_MIDDLE, "Middle"),
+                ] {
+                    let mut enabled = rules.click_buttons & button != 0;
+                    if ui.checkbox(&mut enabled, name).changed() {
+                        rules.click_buttons ^= button;
+                    }
+                }
+            });
+        }
+        ui.add_space(6.0);
+        ui.checkbox(
+            &mut rules.skip_own_window,
+            "Skip saves over the MouseMinder window",
+        );
+
+        // Screen rectangles in physical pixels, e.g. a taskbar. Desktop
+        // coordinates fit in 16 bits on X11 and Windows.
+        let coordinates = -32_768..=32_767;
+        let extents = 0..=65_535;
+        ui.label(RichText::new("Exclusion zones").color(TEXT_COLOR));
+        let mut removed_zone = None;
+        for (index, zone) in rules.zones.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut zone.name).desired_width(80.0));
+                ui.add(
+                    egui::DragValue::new(&mut zone.x)
+                        .range(coordinates.clone())
+                        .prefix("x "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut zone.y)
+                        .range(coordinates.clone())
+                        .prefix("y "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut zone.width)
+                        .range(extents.clone())
+                        .prefix("w "),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut zone.height)
+                        .range(extents.clone())
+                        .prefix("h "),
+                );
+                if ui.small_button("🗑").on_hover_text("Remove zone").clicked() {
+                    removed_zone = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed_zone {
+            rules.zones.remove(index);
+        }
+        if ui.button("Add zone").clicked() {
+            rules.zones.push(ExclusionZone {
+                name: format!("Zone {}", rules.zones.len() + 1),
+                x: 0,
+                y: 0,
+                width: 200,
+                height: 100,
+            });
+        }
+
+        ui.add_space(6.0);
+        ui.label(RichText::new("Window rules").color(TEXT_COLOR));
+        let mut removed_rule = None;
+        for (index, rule) in rules.window_rules.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut rule.class)
+                        .hint_text("Class")
+                        .desired_width(100.0),
+                );
+                ui.add(
+                    egui::TextEdit::singleline(&mut rule.title)
+                        .hint_text("Title")
+                        .desired_width(140.0),
+                );
+                if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                    removed_rule = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed_rule {
+            rules.window_rules.remove(index);
+        }
+        if ui.button("Add window rule").clicked() {
+            rules.window_rules.push(WindowRule::default());
+        }
+        hint(
+            ui,
+            "Windows match when their class and title contain the given text, ignoring case",
+        );
+
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            ui.label("Quiet period after a click");
+            ui.add(
+                egui::DragValue::new(&mut self.settings.save_rules.release_quiet_ms)
+                    .range(0..=10_000)
+                    .speed(10.0)
+                    .suffix(" ms"),
+            );
+        });
+        hint(
+            ui,
+            "Idle saves never happen while a mouse button is held, e.g. during a drag",
+        );
+
+        // Typing, with a policy per application
+        ui.add_space(6.0);
+        combo(
+            ui,
+            "Keyboard",
+            &mut self.settings.save_rules.keyboard_policy,
+            &KeyboardPolicy::ALL,
+            KeyboardPolicy::label,
+        );
+        let profiles = &mut self.settings.save_rules.keyboard_profiles;
+        let mut removed_profile = None;
+        for (index, profile) in profiles.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut profile.class)
+                        .hint_text("Class")
+                        .desired_width(100.0),
+                );
+                egui::ComboBox::from_id_salt(("keyboard_profile", index))
+                    .selected_text(profile.policy.label())
+                    .show_ui(ui, |ui| {
+                        for policy in KeyboardPolicy::ALL {
+                            ui.selectable_value(&mut profile.policy, policy, policy.label());
+                        }
+                    });
+                if ui
+                    .small_button("🗑")
+                    .on_hover_text("Remove application")
+                    .clicked()
+                {
+                    removed_profile = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed_profile {
+            profiles.remove(index);
+        }
+        if ui.button("Add application").clicked() {
+            profiles.push(KeyboardProfile {
+                class: self.focus.class().unwrap_or_default().to_owned(),
+                policy: self.settings.save_rules.keyboard_policy,
+            });
+        }
+        if let Some(app) = self.focus.class() {
+            hint(
+                ui,
+                &format!(
+                    "{} is focused: {}",
+                    app,
+                    self.settings
+                        .save_rules
+                        .keyboard_policy_for(Some(app))
+                        .label()
+                ),
+            );
+        }
+
+        match &self.save_skipped {
+            Some(reason) => hint(ui, &format!("Last save skipped: {}", reason)),
+            None => hint(ui, "No save skipped since the last one"),
+        }
+    }
+
+    fn tour_settings(&mut self, ui: &mut Ui) {
+        let active_set = self.active_set().map(str::to_owned);
+        let saved_position = self.current_position();
+        let tour = &mut self.settings.tour;
+
+        // Named slots the stops refer to
+        ui.label(RichText::new("Slots").color(TEXT_COLOR));
+        let mut removed_slot = None;
+        for (index, slot) in tour.slots.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                // Stops follow their slot when it is renamed
+                let previous_name = slot.name.clone();
+                let renamed = ui
+                    .add(egui::TextEdit::singleline(&mut slot.name).desired_width(120.0))
+                    .changed();
+                if renamed {
+                    for stop in &mut tour.stops {
+                        if stop.slot == previous_name {
+                            stop.slot = slot.name.clone();
+                        }
+                    }
+                }
+                // Window class the slot belongs to, empty for all applications
+                let mut app = slot.app.clone().unwrap_or_default();
+                if ui
+                    .add(
+                        egui::TextEdit::singleline(&mut app)
+                            .hint_text("All apps")
+                            .desired_width(70.0),
+                    )
+                    .on_hover_text("Window class of the application this slot belongs to")
+                    .changed()
+                {
+                    slot.app = Some(app.trim().to_owned()).filter(|app| !app.is_empty());
+                }
+                hint(ui, &slot.position.describe(&self.settings.restore));
+                if slot.position.is_on_missing_monitor(&self.monitors) {
+                    ui.label(RichText::new("⚠").color(WARNING_COLOR))
+                        .on_hover_text("The monitor this slot was saved on is disconnected");
+                }
+                if ui.small_button("🗑").on_hover_text("Remove slot").clicked() {
+                    removed_slot = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed_slot {
+            tour.slots.remove(index);
+        }
+
+        if ui
+            .add_enabled(
+                saved_position.is_some(),
+                egui::Button::new("Add saved position as slot"),
+            )
+            .clicked()
+            && let Some(position) = saved_position
+        {
+            tour.slots.push(Slot {
+                name: tour.next_slot_name(),
+                position,
+                app: active_set.clone(),
+            });
+        }
+
+        if let Some(app) = &active_set {
+            hint(
+                ui,
+                &format!(
+                    "Active set: {}, {} slots",
+                    app,
+                    tour.slots_for(Some(app)).count()
+                ),
+            );
+        }
+
+        // Stops in the order the tour visits them
+        ui.add_space(6.0);
+        ui.label(RichText::new("Stops").color(TEXT_COLOR));
+        let mut removed_stop = None;
+        let mut raised_stop = None;
+        for (index, stop) in tour.stops.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}.", index + 1));
+                egui::ComboBox::from_id_salt(("tour_stop", index))
+                    .selected_text(stop.slot.as_str())
+                    .width(90.0)
+                    .show_ui(ui, |ui| {
+                        for slot in &tour.slots {
+                            ui.selectable_value(&mut stop.slot, slot.name.clone(), &slot.name);
+                        }
+                    });
+                ui.add(
+                    egui::DragValue::new(&mut stop.dwell_secs)
+                        .range(0.0..=60.0)
+                        .speed(0.1)
+                        .suffix(" s"),
+                );
+                ui.checkbox(&mut stop.click, "Click");
+                if index > 0 && ui.small_button("⬆").on_hover_text("Move up").clicked() {
+                    raised_stop = Some(index);
+                }
+                if ui.small_button("🗑").on_hover_text("Remove stop").clicked() {
+                    removed_stop = Some(index);
+                }
+            });
+        }
+        if let Some(index) = raised_stop {
+            tour.stops.swap(index - 1, index);
+        }
+        if let Some(index) = removed_stop {
+            tour.stops.remove(index);
+        }
+
+        if ui
+            .add_enabled(!tour.slots.is_empty(), egui::Button::new("Add stop"))
+            .clicked()
+        {
+            tour.stops.push(TourStop {
+                slot: tour.slots[0].name.clone(),
+                dwell_secs: 2.0,
+                click: false,
+            });
+        }
+
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            let play_label = if !self.tour.is_running() {
+                "▶ Start tour"
+            } else if self.tour.is_paused() {
+                "▶ Resume"
+            } else {
+                "⏸ Pause"
+            };
+            let has_stops = !self.settings.tour.stops.is_empty();
+            if ui
+                .add_enabled(has_stops, egui::Button::new(play_label))
+                .clicked()
+            {
+                self.tour_play_pause();
+            }
+            if ui
+                .add_enabled(has_stops, egui::Button::new("Next stop"))
+                .clicked()
+            {
+                self.tour_next();
+            }
+            if ui
+                .add_enabled(self.tour.is_running(), egui::Button::new("Stop"))
+                .clicked()
+            {
+                self.tour.stop();
+            }
+        });
+        if let Some(status) = self.tour.status(&self.settings.tour) {
+            hint(ui, &status);
+        }
+        if let Some(err) = &self.tour_error {
+            ui.label(RichText::new(err).color(WARNING_COLOR).size(12.0));
+        }
+        // Slot sets carry over between resolutions as monitor fractions
+        ui.add_space(6.0);
+        if ui
+            .add_enabled(
+                !self.settings.tour.slots.is_empty(),
+                egui::Button::new("Export slots"),
+            )
+            .clicked()
+        {
+            self.export_slots();
+        }
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.slot_import_path)
+                    .hint_text("slots-….json")
+                    .desired_width(180.0),
+            );
+            if ui
+                .add_enabled(
+                    !self.slot_import_path.trim().is_empty(),
+                    egui::Button::new("Import slots"),
+                )
+                .clicked()
+            {
+                self.import_slots();
+            }
+        });
+        match &self.slot_status {
+            Some(Ok(message)) => hint(ui, message),
+            Some(Err(message)) => {
+                ui.label(RichText::new(message).color(WARNING_COLOR).size(12.0));
+            }
+            None => {}
+        }
+
+        let hotkeys = if cfg!(target_os = "macos") {
+            "⌘+Shift+T starts and pauses the tour, ⌘+Shift+N skips to the next stop"
+        } else {
+            "Ctrl+Shift+T starts and pauses the tour, Ctrl+Shift+N skips to the next stop"
+        };
+        hint(ui, hotkeys);
+    }
+
+    fn position_settings(&mut self, ui: &mut Ui) {
+        let has_monitors = !self.monitors.is_empty();
+        if !has_monitors {
+            hint(
+                ui,
+                "Monitors cannot be queried here (supported on Linux with X11 and on Windows), \
+                 positions are restored exactly as saved",
+            );
+        }
+        ui.add_enabled(
+            has_monitors,
+            egui::Checkbox::new(
+                &mut self.settings.restore.relative_positions,
+                "Positions as monitor percentages",
+            ),
+        );
+        hint(
+            ui,
+            "Restores land at the same share of the monitor's width and height, \
+             on the matching or primary monitor, so anchors carry over between resolutions",
+        );
+        ui.checkbox(
+            &mut self.settings.restore.window_relative,
+            "Positions relative to their window",
+        );
+        hint(
+            ui,
+            "Restores follow the window the position was saved over, found again by its \
+             class and title, so moving the window keeps the spot (X11 only)",
+        );
+        ui.checkbox(
+            &mut self.settings.restore.per_app_positions,
+            "Positions per application",
+        );
+        hint(
+            ui,
+            &format!(
+                "Each application keeps its own last position and restores use the focused \
+                 application's one. Active set: {}",
+                self.focus.class().unwrap_or("none yet")
+            ),
+        );
+        ui.checkbox(
+            &mut self.settings.restore.focus_window,
+            "Focus the window on restore",
+        );
+        hint(
+            ui,
+            "Raises the window the position was saved over and gives it keyboard focus, \
+             switching virtual desktops if needed (X11 with an EWMH window manager)",
+        );
+        // Where the scale factors shown for positions come from
+        let scale_note = if cfg!(target_os = "linux") {
+            ", X11 has a single scale (Xft.dpi) for all monitors"
+        } else if cfg!(windows) {
+            ", with each monitor's own scale"
+        } else {
+            ""
+        };
+        combo(
+            ui,
+            "Off screen targets",
+            &mut self.settings.restore.bounds_policy,
+            &BoundsPolicy::ALL,
+            BoundsPolicy::label,
+        );
+        hint(
+            ui,
+            &format!(
+                "Cursor positions are read and moved in {}{}",
+                CoordinateSpace::desktop().label(),
+                scale_note
+            ),
+        );
+    }
+
+    fn tray_settings(&mut self, ui: &mut Ui) {
+        let tray_available = self.tray.is_some();
+        ui.add_enabled(
+            tray_available,
+            egui::Checkbox::new(&mut self.settings.close_to_tray, "Close window to tray"),
+        );
+        ui.checkbox(&mut self.settings.start_minimized, "Start minimized");
+        if !tray_available {
+            hint(ui, "System tray is not available, minimizing instead");
+        }
+    }
+
+    fn startup_settings(&mut self, ui: &mut Ui) {
+        if self.autostart_status == AutostartStatus::Unsupported {
+            hint(ui, "Starting at login is only supported on Linux");
+            return;
+        }
+
+        let mut enabled = self.autostart_status.is_enabled();
+        if ui.checkbox(&mut enabled, "Start at login").changed() {
+            self.set_autostart(enabled);
+        }
+
+        let tracking_changed = ui
+            .add_enabled(
+                enabled,
+                egui::Checkbox::new(
+                    &mut self.settings.autostart_start_tracking,
+                    "Start tracking at login",
+                ),
+            )
+            .changed();
+        if tracking_changed {
+            self.set_autostart(true);
+        }
+
+        if let AutostartStatus::Stale(program) = &self.autostart_status {
+            ui.label(
+                RichText::new(format!("Login entry launches another binary: {}", program))
+                    .color(WARNING_COLOR)
+                    .size(12.0),
+            );
+            if ui.button("Update entry").clicked() {
+                self.set_autostart(true);
+            }
+        }
+
+        if let Some(err) = &self.autostart_error {
+            ui.label(
+                RichText::new(format!("Failed to update login entry: {}", err))
+                    .color(WARNING_COLOR)
+                    .size(12.0),
+            );
+        }
+    }
+
+    fn overlay_settings(&mut self, ui: &mut Ui) {
+        let overlay = &mut self.settings.overlay;
+        ui.checkbox(&mut overlay.enabled, "Mark saved positions on screen");
+
+        ui.add_enabled_ui(overlay.enabled, |ui| {
+            combo(
+                ui,
+                "Style",
+                &mut overlay.style,
+                &MarkerStyle::ALL,
+                MarkerStyle::label,
+            );
+            combo(
+                ui,
+                "Show",
+                &mut overlay.mode,
+                &OverlayMode::ALL,
+                OverlayMode::label,
+            );
+
+            match overlay.mode {
+                OverlayMode::Always => {}
+                OverlayMode::WhileModifierHeld => combo(
+                    ui,
+                    "Modifier",
+                    &mut overlay.modifier,
+                    &OverlayModifier::ALL,
+                    OverlayModifier::label,
+                ),
+                OverlayMode::AfterSave => {
+                    ui.add(
+                        egui::Slider::new(&mut overlay.duration_secs, 1.0..=10.0).text("seconds"),
+                    );
+                }
+            }
+        });
+    }
+
+    fn pulse_settings(&mut self, ui: &mut Ui) {
+        let pulse = &mut self.settings.pulse;
+        ui.checkbox(&mut pulse.enabled, "Highlight the cursor after restoring");
+
+        ui.add_enabled_ui(pulse.enabled, |ui| {
+            ui.checkbox(&mut pulse.hide_while_recording, "Hide while recording");
+            ui.horizontal(|ui| {
+                ui.label("Color");
+                ui.color_edit_button_srgb(&mut pulse.color);
+            });
+            ui.add(egui::Slider::new(&mut pulse.radius, 10.0..=150.0).text("radius"));
+            ui.add(egui::Slider::new(&mut pulse.duration_secs, 0.2..=3.0).text("seconds"));
+        });
+    }
+
+    fn notification_settings(&mut self, ui: &mut Ui) {
+        let notifications = &mut self.settings.notifications;
+        ui.checkbox(&mut notifications.position_saved, "Position saved");
+        ui.checkbox(&mut notifications.restore_failed, "Restore failed");
+        ui.checkbox(&mut notifications.hotkey_error, "Hotkey unavailable");
+        ui.add(
+            egui::Slider::new(&mut notifications.min_interval_secs, 0.0..=60.0)
+                .text("seconds apart"),
+        );
+        hint(ui, "Repeated notifications of the same kind are skipped");
+    }
+}
+
+// Titled group of settings
+fn section(ui: &mut Ui, title: &str, add_contents: impl FnOnce(&mut Ui)) {
+    egui::Frame::new()
+        .fill(Color32::from_rgb(45, 45, 50))
+        .corner_radius(CornerRadius::same(8))
+        .inner_margin(egui::Margin::same(12))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.label(RichText::new(title).color(TEXT_COLOR).size(16.0).strong());
+            ui.add_space(6.0);
+            add_contents(ui);
+        });
+    ui.add_space(12.0);
+}
+
+// Small explanatory text below a setting
+fn hint(ui: &mut Ui, text: &str) {
+    ui.label(RichText::new(text).color(HINT_COLOR).size(12.0));
+}
+
+// Labeled drop-down for picking one of a fixed set of options
+fn combo<T: Copy + PartialEq>(
+    ui: &mut Ui,
+    label: &str,
+    value: &mut T,
+    options: &[T],
+    option_label: fn(T) -> &'static str,
+) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        egui::ComboBox::from_id_salt(label)
+            .selected_text(option_label(*value))
+            .show_ui(ui, |ui| {
+                for &option in options {
+                    ui.selectable_value(value, option, option_label(option));
+                }
+            });
+    });
+}
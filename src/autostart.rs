@@ -0,0 +1,218 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+const ENTRY_FILE_NAME: &str = "mouse_minder.desktop";
+
+// State of the XDG autostart entry
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AutostartStatus {
+    Unsupported,
+    Disabled,
+    Enabled,
+    // The entry launches a binary other than the one running now
+    Stale(String),
+}
+
+impl AutostartStatus {
+    pub fn is_enabled(&self) -> bool {
+        matches!(self, Self::Enabled | Self::Stale(_))
+    }
+}
+
+// Inspect the autostart entry on disk
+pub fn status() -> AutostartStatus {
+    let Some(path) = entry_path() else {
+        return AutostartStatus::Unsupported;
+    };
+    let Ok(contents) = fs::read_to_string(path) else {
+        return AutostartStatus::Disabled;
+    };
+
+    let program = contents
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Exec="))
+        .and_then(exec_program);
+    let current_exe = std::env::current_exe().ok();
+
+    match (program, current_exe) {
+        (Some(program), Some(exe)) if exe == Path::new(&program) => AutostartStatus::Enabled,
+        (Some(program), _) => AutostartStatus::Stale(program),
+        (None, _) => AutostartStatus::Stale(String::new()),
+    }
+}
+
+// Write the autostart entry for the running binary
+pub fn enable(start_tracking: bool) -> io::Result<()> {
+    let path = entry_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "autostart not supported"))?;
+    let exe = std::env::current_exe()?;
+
+    let mut exec = quote_exec_arg(&exe.to_string_lossy());
+    exec.push_str(" --daemon");
+    if start_tracking {
+        exec.push_str(" --start-tracking");
+    }
+
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name={}\n\
+         Comment=Track and restore mouse positions\n\
+         Exec={}\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        config::APP_NAME,
+        exec
+    );
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, entry)
+}
+
+// Remove the autostart entry
+pub fn disable() -> io::Result<()> {
+    match entry_path().map(fs::remove_file) {
+        Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+// ~/.config/autostart/mouse_minder.desktop, honoring XDG_CONFIG_HOME
+fn entry_path() -> Option<PathBuf> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+
+    let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_dir.join("autostart").join(ENTRY_FILE_NAME))
+}
+
+// Quote an argument for a desktop entry Exec key. The Exec value is
+// quoted first and then escaped again as a desktop entry string, so
+// a backslash in the path ends up as four
+fn quote_exec_arg(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    let needs_quotes = |c: char| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c);
+    let quoted = if arg.contains(needs_quotes) {
+        let mut quoted = String::from("\"");
+        for c in arg.chars() {
+            if matches!(c, '"' | '`' | '$' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    } else {
+        arg
+    };
+    escape_string(&quoted)
+}
+
+// Extract the program from an Exec value, undoing quote_exec_arg
+fn exec_program(exec: &str) -> Option<String> {
+    let exec = unescape_string(exec.trim());
+    let program = match exec.strip_prefix('"') {
+        Some(rest) => {
+            let mut program = String::new();
+            let mut chars = rest.chars();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => program.push(chars.next()?),
+                    c => program.push(c),
+                }
+            }
+            program
+        }
+        None => exec.split_whitespace().next()?.to_string(),
+    };
+    Some(program.replace("%%", "%"))
+}
+
+// Escape a value of type string in a desktop entry
+fn escape_string(value: &str) -> String {
+    let mut escaped = String::new();
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Undo escape_string, also accepting \s for a space
+fn unescape_string(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(path: &str) -> Option<String> {
+        exec_program(&format!("{} --daemon", quote_exec_arg(path)))
+    }
+
+    #[test]
+    fn plain_paths_are_not_quoted() {
+        assert_eq!(
+            quote_exec_arg("/usr/bin/mouse_minder"),
+            "/usr/bin/mouse_minder"
+        );
+        assert_eq!(
+            round_trip("/usr/bin/mouse_minder").unwrap(),
+            "/usr/bin/mouse_minder"
+        );
+    }
+
+    #[test]
+    fn backslashes_are_escaped_twice() {
+        assert_eq!(quote_exec_arg(r"/opt/a\b"), r#""/opt/a\\\\b""#);
+        assert_eq!(quote_exec_arg(r#"/opt/say "hi""#), r#""/opt/say \\"hi\\"""#);
+    }
+
+    #[test]
+    fn paths_survive_a_round_trip() {
+        for path in [
+            "/home/me/My Apps/mouse_minder",
+            r#"/home/me/"quoted"/mouse_minder"#,
+            "/home/me/$HOME/mouse_minder",
+            r"/home/me/back\slash/mouse_minder",
+            r"/home/me/\s and \n/mouse_minder",
+            "/home/me/100%/mouse_minder",
+            "/home/me/`tick`'s/mouse_minder",
+            "/home/me/line\nbreak/mouse_minder",
+        ] {
+            assert_eq!(round_trip(path).as_deref(), Some(path));
+        }
+    }
+}
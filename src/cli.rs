@@ -0,0 +1,115 @@
+// Commands that can be passed on the command line, either to this process at
+// launch or forwarded to an already running instance
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Show,
+    Hide,
+    Restore,
+    // Restore to the tour slot at this index of the active set's slots
+    RestoreSlot(usize),
+    SaveNow,
+    Reset,
+    StartTracking,
+    StopTracking,
+    ToggleTracking,
+    StartRecording,
+    StopRecording,
+    ExportSession,
+    ExportMarkers,
+    ExportPath,
+    ReplayPath,
+    AbortReplay,
+    TourPlayPause,
+    TourNext,
+    TourStop,
+    Quit,
+}
+
+impl Command {
+    // Parse a single command line flag
+    pub fn from_flag(flag: &str) -> Option<Self> {
+        // Slots are numbered from 1 on the command line
+        if let Some(number) = flag.strip_prefix("--restore-slot=") {
+            let number: usize = number.parse().ok()?;
+            return number.checked_sub(1).map(Self::RestoreSlot);
+        }
+
+        match flag {
+            "--show" => Some(Self::Show),
+            "--hide" => Some(Self::Hide),
+            "--restore" => Some(Self::Restore),
+            "--save-now" => Some(Self::SaveNow),
+            "--reset" => Some(Self::Reset),
+            "--start-tracking" => Some(Self::StartTracking),
+            "--stop-tracking" => Some(Self::StopTracking),
+            "--toggle-tracking" => Some(Self::ToggleTracking),
+            "--start-recording" => Some(Self::StartRecording),
+            "--stop-recording" => Some(Self::StopRecording),
+            "--export-session" => Some(Self::ExportSession),
+            "--export-markers" => Some(Self::ExportMarkers),
+            "--export-path" => Some(Self::ExportPath),
+            "--replay-path" => Some(Self::ReplayPath),
+            "--abort-replay" => Some(Self::AbortReplay),
+            "--tour-play-pause" => Some(Self::TourPlayPause),
+            "--tour-next" => Some(Self::TourNext),
+            "--tour-stop" => Some(Self::TourStop),
+            "--quit" => Some(Self::Quit),
+            _ => None,
+        }
+    }
+
+    // The flag used to forward this command to another instance
+    pub fn flag(self) -> String {
+        let flag = match self {
+            Self::Show => "--show",
+            Self::Hide => "--hide",
+            Self::Restore => "--restore",
+            Self::RestoreSlot(index) => return format!("--restore-slot={}", index + 1),
+            Self::SaveNow => "--save-now",
+            Self::Reset => "--reset",
+            Self::StartTracking => "--start-tracking",
+            Self::StopTracking => "--stop-tracking",
+            Self::ToggleTracking => "--toggle-tracking",
+            Self::StartRecording => "--start-recording",
+            Self::StopRecording => "--stop-recording",
+            Self::ExportSession => "--export-session",
+            Self::ExportMarkers => "--export-markers",
+            Self::ExportPath => "--export-path",
+            Self::ReplayPath => "--replay-path",
+            Self::AbortReplay => "--abort-replay",
+            Self::TourPlayPause => "--tour-play-pause",
+            Self::TourNext => "--tour-next",
+            Self::TourStop => "--tour-stop",
+            Self::Quit => "--quit",
+        };
+        flag.into()
+    }
+}
+
+// Parsed launch arguments
+#[derive(Clone, Debug, Default)]
+pub struct LaunchArgs {
+    pub commands: Vec<Command>,
+    // Start hidden in the tray, used when launched at login
+    pub daemon: bool,
+}
+
+impl LaunchArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut launch = Self::default();
+
+        for arg in args {
+            if arg == "--daemon" {
+                launch.daemon = true;
+                continue;
+            }
+
+            match Command::from_flag(&arg) {
+                Some(command) => launch.commands.push(command),
+                None => eprintln!("Ignoring unknown argument: {}", arg),
+            }
+        }
+
+        launch
+    }
+}
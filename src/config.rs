@@ -5,5 +5,8 @@ pub const APP_VERSION: &str = "1.0.0";
 // Tracking settings
 pub const INACTIVITY_THRESHOLD_MS: u64 = 2000; // 2 seconds
 pub const POLL_INTERVAL_MS: u64 = 50;          // Mouse polling interval
+pub const PATH_SAMPLE_INTERVAL_MS: u64 = 8;    // Polling interval while recording a path
 pub const UI_REFRESH_INTERVAL_MS: u64 = 100;   // UI refresh rate
+pub const FOCUS_POLL_INTERVAL_MS: u64 = 250;   // Polling interval of the focused application
+pub const FOCUS_WAIT_MS: u64 = 300;            // Longest wait for the window manager to focus a window
 pub const FEEDBACK_DURATION_MS: u64 = 2000;    // Duration of visual feedback
\ No newline at end of file
@@ -0,0 +1,96 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Folder exports go to unless the user picks another one
+pub fn default_export_dir() -> String {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join("MouseMinder"))
+        .unwrap_or_else(|| PathBuf::from("MouseMinder"))
+        .to_string_lossy()
+        .into_owned()
+}
+
+// Write a file into the export folder, named after its kind and the time
+pub fn write_export(
+    dir: &str,
+    kind: &str,
+    extension: &str,
+    contents: impl AsRef<[u8]>,
+) -> io::Result<PathBuf> {
+    let dir = Path::new(dir);
+    fs::create_dir_all(dir)?;
+
+    let path = dir.join(format!(
+        "{}-{}.{}",
+        kind,
+        file_stamp(SystemTime::now()),
+        extension
+    ));
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+// UTC time as 2024-01-31T12:34:56.789012Z
+pub fn rfc3339(time: SystemTime) -> String {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let (year, month, day) = civil_from_days((duration.as_secs() / 86400) as i64);
+    let secs = duration.as_secs() % 86400;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60,
+        duration.subsec_micros()
+    )
+}
+
+// UTC time as 20240131-123456 for file names
+fn file_stamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let secs = secs % 86400;
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+// Convert days since 1970-01-01 to a (year, month, day) date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's days_from_civil inverse
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// Quote a CSV field if it needs it
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -2,51 +2,129 @@ use global_hotkey::{
     GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
     hotkey::{Code, HotKey, Modifiers},
 };
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{self, Sender};
 use std::thread::{self, JoinHandle};
 
 // Actions that can be triggered by hotkeys
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum HotKeyAction {
     RestorePosition,
+    SaveNow,
+    ToggleTracking,
+    AbortReplay,
+    TourPlayPause,
+    TourNext,
 }
 
 // Hotkey handling system
 pub struct HotKeySystem {
     _listener_thread: JoinHandle<()>, // Keep thread alive with the struct
+    // Hotkeys another application already owns, one message each
+    failures: Vec<String>,
 }
 
 impl HotKeySystem {
+    // Register the hotkeys. Each one is registered on its own so a hotkey
+    // another application owns does not take the others down with it, the
+    // ones that failed are kept in failures().
     pub fn new(action_sender: Sender<HotKeyAction>) -> Result<Self, Box<dyn std::error::Error>> {
+        // Registration happens on the listener thread, report its outcome back
+        let (result_sender, result_receiver) = mpsc::channel::<Result<Vec<String>, String>>();
+
         // Start a thread to handle hotkey registration and events
         let listener_thread = thread::spawn(move || {
-            if let Ok(manager) = GlobalHotKeyManager::new() {
-                // Determine platform specific modifier (Cmd for macOS, Ctrl for others)
-                let modifier = if cfg!(target_os = "macos") {
-                    Modifiers::META | Modifiers::SHIFT // Change CMD to META
-                } else {
-                    Modifiers::CONTROL | Modifiers::SHIFT
-                };
-
-                // Create and register the restore position hotkey (R key)
-                let restore_hotkey = HotKey::new(Some(modifier), Code::KeyR);
-                if manager.register(restore_hotkey).is_ok() {
-                    // Record the mapping of hotkey ID to action
-                    let restore_id = restore_hotkey.id();
-
-                    // Listen for hotkey events
-                    let event_receiver = GlobalHotKeyEvent::receiver();
-                    while let Ok(event) = event_receiver.recv() {
-                        if event.state == HotKeyState::Pressed && event.id == restore_id {
-                            let _ = action_sender.send(HotKeyAction::RestorePosition);
-                        }
-                    }
+            let manager = match GlobalHotKeyManager::new() {
+                Ok(manager) => manager,
+                Err(err) => {
+                    let _ = result_sender.send(Err(err.to_string()));
+                    return;
+                }
+            };
+
+            // Determine platform specific modifier (Cmd for macOS, Ctrl for others)
+            let modifier = if cfg!(target_os = "macos") {
+                Modifiers::META | Modifiers::SHIFT // Change CMD to META
+            } else {
+                Modifiers::CONTROL | Modifiers::SHIFT
+            };
+
+            // Hotkeys and the actions they trigger
+            let bindings = [
+                // Restore position (R key)
+                (
+                    "restore",
+                    HotKey::new(Some(modifier), Code::KeyR),
+                    HotKeyAction::RestorePosition,
+                ),
+                // Save the cursor position right away (M key)
+                (
+                    "save position",
+                    HotKey::new(Some(modifier), Code::KeyM),
+                    HotKeyAction::SaveNow,
+                ),
+                // Start and stop tracking (K key)
+                (
+                    "toggle tracking",
+                    HotKey::new(Some(modifier), Code::KeyK),
+                    HotKeyAction::ToggleTracking,
+                ),
+                // Stop a running path replay (X key)
+                (
+                    "abort replay",
+                    HotKey::new(Some(modifier), Code::KeyX),
+                    HotKeyAction::AbortReplay,
+                ),
+                // Start, pause and resume the tour (T key)
+                (
+                    "tour",
+                    HotKey::new(Some(modifier), Code::KeyT),
+                    HotKeyAction::TourPlayPause,
+                ),
+                // Jump to the next tour stop (N key)
+                (
+                    "next tour stop",
+                    HotKey::new(Some(modifier), Code::KeyN),
+                    HotKeyAction::TourNext,
+                ),
+            ];
+            let failures = bindings
+                .iter()
+                .filter_map(|(name, hotkey, _)| {
+                    manager
+                        .register(*hotkey)
+                        .err()
+                        .map(|err| format!("Could not register the {} hotkey: {}", name, err))
+                })
+                .collect();
+            let _ = result_sender.send(Ok(failures));
+
+            // Listen for hotkey events
+            let event_receiver = GlobalHotKeyEvent::receiver();
+            while let Ok(event) = event_receiver.recv() {
+                if event.state != HotKeyState::Pressed {
+                    continue;
+                }
+                // Map the hotkey ID back to its action
+                if let Some((_, _, action)) = bindings
+                    .iter()
+                    .find(|(_, hotkey, _)| hotkey.id() == event.id)
+                {
+                    let _ = action_sender.send(*action);
                 }
             }
         });
 
+        let failures = result_receiver
+            .recv()
+            .map_err(|_| "Hotkey thread exited unexpectedly".to_string())??;
+
         Ok(Self {
             _listener_thread: listener_thread,
+            failures,
         })
     }
+
+    pub fn failures(&self) -> &[String] {
+        &self.failures
+    }
 }
@@ -0,0 +1,221 @@
+use egui::Context;
+use std::fs::{self, File, TryLockError};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// How long a second launch waits for the running instance to start listening
+const CONNECT_ATTEMPTS: u32 = 20;
+const CONNECT_RETRY_MS: u64 = 50;
+
+#[cfg(not(unix))]
+use std::net::{TcpListener as Listener, TcpStream as Stream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener as Listener, UnixStream as Stream};
+
+use crate::cli::{Command, LaunchArgs};
+
+// Outcome of trying to become the running instance
+pub enum Instance {
+    Primary(InstanceLock),
+    Forwarded,
+}
+
+// Per-user lock held by the running instance. Other launches connect to its
+// socket to forward their commands instead of starting a second tracker.
+pub struct InstanceLock {
+    // Locked for the lifetime of the process, released by the OS on exit
+    lock_file: File,
+    listener: Listener,
+    path: PathBuf,
+}
+
+impl InstanceLock {
+    // Take the lock, or forward the commands to the instance holding it
+    pub fn acquire(launch: &LaunchArgs) -> io::Result<Instance> {
+        let path = socket_path();
+        if let Some(dir) = path.parent() {
+            create_private_dir(dir)?;
+        }
+
+        let lock_file = File::create(path.with_extension("lock"))?;
+        match lock_file.try_lock() {
+            Ok(()) => {}
+            // Another instance runs, it may still be setting up its socket
+            Err(TryLockError::WouldBlock) => {
+                // A login start finding MouseMinder running has nothing to
+                // ask of it, while forwarding nothing would show the window
+                if !(launch.daemon && launch.commands.is_empty()) {
+                    forward(connect_with_retry(&path)?, &launch.commands)?;
+                }
+                return Ok(Instance::Forwarded);
+            }
+            Err(TryLockError::Error(err)) => return Err(err),
+        }
+
+        // Only the lock holder gets here, a socket left behind is stale
+        let _ = fs::remove_file(&path);
+        let listener = bind(&path)?;
+        Ok(Instance::Primary(Self {
+            lock_file,
+            listener,
+            path,
+        }))
+    }
+
+    // Start accepting forwarded commands from other launches
+    pub fn listen(self, command_sender: Sender<Command>, ctx: Context) -> InstanceListener {
+        let listener = self.listener;
+        let listener_thread = thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut commands: Vec<Command> = BufReader::new(stream)
+                    .lines()
+                    .map_while(Result::ok)
+                    .filter_map(|line| Command::from_flag(line.trim()))
+                    .collect();
+
+                // A bare relaunch just asks for the window
+                if commands.is_empty() {
+                    commands.push(Command::Show);
+                }
+
+                for command in commands {
+                    if command_sender.send(command).is_err() {
+                        return;
+                    }
+                }
+                ctx.request_repaint();
+            }
+        });
+
+        InstanceListener {
+            _listener_thread: listener_thread,
+            _lock_file: self.lock_file,
+            path: self.path,
+        }
+    }
+}
+
+// Keeps the lock alive and removes it when the app shuts down
+pub struct InstanceListener {
+    _listener_thread: JoinHandle<()>,
+    _lock_file: File,
+    path: PathBuf,
+}
+
+impl Drop for InstanceListener {
+    fn drop(&mut self) {
+        // The lock file stays, removing it would let a launch that already
+        // opened it lock a different file than the next one
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn connect_with_retry(path: &Path) -> io::Result<Stream> {
+    let mut attempt = 1;
+    loop {
+        match connect(path) {
+            Ok(stream) => return Ok(stream),
+            Err(err) if attempt >= CONNECT_ATTEMPTS => return Err(err),
+            Err(_) => {
+                attempt += 1;
+                thread::sleep(Duration::from_millis(CONNECT_RETRY_MS));
+            }
+        }
+    }
+}
+
+// Send commands to the running instance, one flag per line
+fn forward(mut stream: Stream, commands: &[Command]) -> io::Result<()> {
+    for command in commands {
+        writeln!(stream, "{}", command.flag())?;
+    }
+    stream.flush()
+}
+
+// Location of the socket, or the port file without Unix sockets, scoped to
+// the current user. The lock file sits next to it.
+fn socket_path() -> PathBuf {
+    let name = if cfg!(unix) {
+        "mouse_minder.sock"
+    } else {
+        "mouse_minder.port"
+    };
+
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join(name),
+        None => std::env::temp_dir().join(fallback_dir_name()).join(name),
+    }
+}
+
+// Create the socket's directory readable by us only, and refuse one another
+// user made to catch our commands
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    if fs::metadata(dir)?.uid() != uid() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} belongs to another user", dir.display()),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+#[cfg(unix)]
+fn uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail
+    unsafe { libc::getuid() }
+}
+
+// The temp dir is shared between users on Unix, ours is named after the uid
+// rather than a user name that may be unset
+#[cfg(unix)]
+fn fallback_dir_name() -> String {
+    format!("mouse_minder-{}", uid())
+}
+
+// Windows gives every user a temp dir of their own
+#[cfg(not(unix))]
+fn fallback_dir_name() -> String {
+    "mouse_minder".into()
+}
+
+#[cfg(unix)]
+fn connect(path: &Path) -> io::Result<Stream> {
+    Stream::connect(path)
+}
+
+#[cfg(unix)]
+fn bind(path: &Path) -> io::Result<Listener> {
+    Listener::bind(path)
+}
+
+// Without Unix sockets the lock file holds the loopback port we listen on
+#[cfg(not(unix))]
+fn connect(path: &Path) -> io::Result<Stream> {
+    let port: u16 = fs::read_to_string(path)?
+        .trim()
+        .parse()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid port in lock file"))?;
+    Stream::connect(("127.0.0.1", port))
+}
+
+#[cfg(not(unix))]
+fn bind(path: &Path) -> io::Result<Listener> {
+    let listener = Listener::bind(("127.0.0.1", 0))?;
+    fs::write(path, listener.local_addr()?.port().to_string())?;
+    Ok(listener)
+}
@@ -1,29 +1,79 @@
 mod app;
+mod autostart;
+mod cli;
 mod config;
+mod export;
 mod hotkeys;
+mod instance;
+mod markers;
+mod monitors;
+mod notifications;
+mod overlay;
+mod path;
+mod replay;
+mod rules;
+mod session;
+mod settings;
+mod tour;
 mod tracker;
+mod tray;
+mod windows;
 
 use app::MouseMinderApp;
+use cli::LaunchArgs;
 use eframe::{Frame, NativeOptions, egui};
+use instance::{Instance, InstanceLock};
+use settings::Settings;
 
 impl eframe::App for MouseMinderApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
         self.update(ctx);
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.save(storage);
+    }
+
+    // Overlay viewports draw on a fully transparent background
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        [0.0; 4]
+    }
 }
 
 fn main() -> Result<(), eframe::Error> {
+    let launch = LaunchArgs::parse(std::env::args().skip(1));
+
+    // Only one instance may own the tracker and hotkeys, later launches hand
+    // their commands over to it
+    let instance_lock = match InstanceLock::acquire(&launch) {
+        Ok(Instance::Primary(lock)) => Some(lock),
+        Ok(Instance::Forwarded) => return Ok(()),
+        Err(err) => {
+            eprintln!("Failed to take the single instance lock: {}", err);
+            None
+        }
+    };
+
     let options = NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([360.0, 650.0])
             .with_resizable(false)
-            .with_min_inner_size([360.0, 650.0]),
+            .with_min_inner_size([360.0, 650.0])
+            // Needed so the overlay viewports can be transparent
+            .with_transparent(true),
         ..Default::default()
     };
 
     eframe::run_native(
         config::APP_NAME,
         options,
-        Box::new(|cc| Ok(Box::new(MouseMinderApp::new(&cc.egui_ctx)))),
+        Box::new(|cc| {
+            Ok(Box::new(MouseMinderApp::new(
+                &cc.egui_ctx,
+                Settings::load(cc.storage),
+                instance_lock,
+                launch,
+            )))
+        }),
     )
 }
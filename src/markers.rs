@@ -0,0 +1,390 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::export::csv_field;
+use crate::session::SessionLog;
+use crate::tracker::TrackerEvent;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrameRate {
+    Fps23976,
+    Fps24,
+    Fps25,
+    Fps2997,
+    Fps30,
+    Fps50,
+    Fps5994,
+    Fps60,
+}
+
+impl FrameRate {
+    pub const ALL: [Self; 8] = [
+        Self::Fps23976,
+        Self::Fps24,
+        Self::Fps25,
+        Self::Fps2997,
+        Self::Fps30,
+        Self::Fps50,
+        Self::Fps5994,
+        Self::Fps60,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Fps23976 => "23.976",
+            Self::Fps24 => "24",
+            Self::Fps25 => "25",
+            Self::Fps2997 => "29.97",
+            Self::Fps30 => "30",
+            Self::Fps50 => "50",
+            Self::Fps5994 => "59.94",
+            Self::Fps60 => "60",
+        }
+    }
+
+    // Whole frames per second used for non-drop-frame timecode
+    fn timebase(self) -> u64 {
+        match self {
+            Self::Fps23976 | Self::Fps24 => 24,
+            Self::Fps25 => 25,
+            Self::Fps2997 | Self::Fps30 => 30,
+            Self::Fps50 => 50,
+            Self::Fps5994 | Self::Fps60 => 60,
+        }
+    }
+
+    fn is_ntsc(self) -> bool {
+        matches!(self, Self::Fps23976 | Self::Fps2997 | Self::Fps5994)
+    }
+
+    // Frame duration as a fraction of a second (numerator, denominator)
+    fn frame_duration(self) -> (u64, u64) {
+        if self.is_ntsc() {
+            (1001, self.timebase() * 1000)
+        } else {
+            (100, self.timebase() * 100)
+        }
+    }
+
+    fn fps(self) -> f64 {
+        let (num, den) = self.frame_duration();
+        den as f64 / num as f64
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkerSettings {
+    pub frame_rate: FrameRate,
+    pub start_timecode: String,
+}
+
+impl Default for MarkerSettings {
+    fn default() -> Self {
+        Self {
+            frame_rate: FrameRate::Fps30,
+            start_timecode: "01:00:00:00".into(),
+        }
+    }
+}
+
+impl MarkerSettings {
+    // Parse the start timecode (HH:MM:SS:FF, non-drop-frame) into frames.
+    // The ; separator marks drop-frame timecode, which is refused
+    pub fn start_frame(&self) -> Option<u64> {
+        let parts: Vec<u64> = self
+            .start_timecode
+            .trim()
+            .split(':')
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        let [hours, minutes, seconds, frames] = parts[..] else {
+            return None;
+        };
+
+        let timebase = self.frame_rate.timebase();
+        if minutes >= 60 || seconds >= 60 || frames >= timebase {
+            return None;
+        }
+        Some(((hours * 60 + minutes) * 60 + seconds) * timebase + frames)
+    }
+}
+
+// A restore placed on the recording timeline
+struct Marker {
+    // Frames since the recording started
+    frame: u64,
+    name: String,
+    note: String,
+}
+
+// Restore markers for a session in CMX3600 EDL, FCPXML 1.10 and DaVinci
+// Resolve marker CSV
+pub struct MarkerExport {
+    markers: Vec<Marker>,
+    frame_rate: FrameRate,
+    start_frame: u64,
+}
+
+impl MarkerExport {
+    pub fn new(session: &SessionLog, settings: &MarkerSettings) -> Result<Self, String> {
+        // Offsets from app launch would not line up with any recording
+        if !session.is_recorded() {
+            return Err("Nothing was recorded yet, start a recording first".into());
+        }
+        let start_frame = settings
+            .start_frame()
+            .ok_or_else(|| format!("Invalid start timecode: {}", settings.start_timecode))?;
+        let fps = settings.frame_rate.fps();
+
+        let markers = session
+            .entries()
+            .iter()
+            .filter_map(|entry| match &entry.event {
+                TrackerEvent::PositionRestored { position, .. } => Some((entry, position)),
+                _ => None,
+            })
+            .enumerate()
+            .map(|(index, (entry, pos))| Marker {
+                frame: (entry.offset.as_secs_f64() * fps).round() as u64,
+                name: format!("Restore {}", index + 1),
+                note: format!("X: {}, Y: {}", pos.x, pos.y),
+            })
+            .collect();
+
+        Ok(Self {
+            markers,
+            frame_rate: settings.frame_rate,
+            start_frame,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.markers.len()
+    }
+
+    // Non-drop-frame timecode for a frame count
+    fn timecode(&self, frames: u64) -> String {
+        let timebase = self.frame_rate.timebase();
+        let seconds = frames / timebase;
+        format!(
+            "{:02}:{:02}:{:02}:{:02}",
+            seconds / 3600,
+            (seconds / 60) % 60,
+            seconds % 60,
+            frames % timebase
+        )
+    }
+
+    // Rational time string used by FCPXML
+    fn fcpxml_time(&self, frames: u64) -> String {
+        let (num, den) = self.frame_rate.frame_duration();
+        format!("{}/{}s", frames * num, den)
+    }
+
+    pub fn to_edl(&self) -> String {
+        let mut edl = format!(
+            "TITLE: {} Restores\nFCM: NON-DROP FRAME\n\n",
+            config::APP_NAME
+        );
+
+        for (index, marker) in self.markers.iter().enumerate() {
+            let record_in = self.timecode(self.start_frame + marker.frame);
+            let record_out = self.timecode(self.start_frame + marker.frame + 1);
+            edl.push_str(&format!(
+                "{:03}  AX       V     C        {} {} {} {}\n",
+                index + 1,
+                record_in,
+                record_out,
+                record_in,
+                record_out
+            ));
+            edl.push_str(&format!("* FROM CLIP NAME: {}\n", marker.name));
+            edl.push_str(&format!(
+                "* LOC: {} BLUE    {} {}\n\n",
+                record_in, marker.name, marker.note
+            ));
+        }
+        edl
+    }
+
+    pub fn to_fcpxml(&self) -> String {
+        let (num, den) = self.frame_rate.frame_duration();
+        // Leave some room after the last marker
+        let duration =
+            self.markers.last().map_or(0, |marker| marker.frame) + self.frame_rate.timebase();
+        let start = self.fcpxml_time(self.start_frame);
+
+        let mut markers = String::new();
+        for marker in &self.markers {
+            markers.push_str(&format!(
+                "                            <marker start=\"{}\" duration=\"{}/{}s\" value=\"{}\" note=\"{}\"/>\n",
+                self.fcpxml_time(self.start_frame + marker.frame),
+                num,
+                den,
+                xml_escape(&marker.name),
+                xml_escape(&marker.note)
+            ));
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE fcpxml>
+<fcpxml version="1.10">
+    <resources>
+        <format id="r1" frameDuration="{num}/{den}s" width="1920" height="1080"/>
+    </resources>
+    <library>
+        <event name="{app}">
+            <project name="{app} Restores">
+                <sequence format="r1" tcStart="{start}" tcFormat="NDF" duration="{duration}">
+                    <spine>
+                        <gap name="Gap" offset="{start}" start="{start}" duration="{duration}">
+{markers}                        </gap>
+                    </spine>
+                </sequence>
+            </project>
+        </event>
+    </library>
+</fcpxml>
+"#,
+            num = num,
+            den = den,
+            app = config::APP_NAME,
+            start = start,
+            duration = self.fcpxml_time(duration),
+            markers = markers,
+        )
+    }
+
+    pub fn to_resolve_csv(&self) -> String {
+        let mut csv = String::from("#,Name,Record In,Record Out,Duration,Color,Notes\n");
+        for (index, marker) in self.markers.iter().enumerate() {
+            let frame = self.start_frame + marker.frame;
+            csv.push_str(&format!(
+                "{},{},{},{},{},Blue,{}\n",
+                index + 1,
+                csv_field(&marker.name),
+                self.timecode(frame),
+                self.timecode(frame + 1),
+                self.timecode(1),
+                csv_field(&marker.note)
+            ));
+        }
+        csv
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(frame_rate: FrameRate, start_timecode: &str) -> MarkerSettings {
+        MarkerSettings {
+            frame_rate,
+            start_timecode: start_timecode.into(),
+        }
+    }
+
+    fn export(frame_rate: FrameRate) -> MarkerExport {
+        MarkerExport {
+            markers: Vec::new(),
+            frame_rate,
+            start_frame: 0,
+        }
+    }
+
+    #[test]
+    fn parses_start_timecode() {
+        assert_eq!(
+            settings(FrameRate::Fps30, "01:00:00:00").start_frame(),
+            Some(108_000)
+        );
+        assert_eq!(
+            settings(FrameRate::Fps25, "00:00:01:05").start_frame(),
+            Some(30)
+        );
+        assert_eq!(
+            settings(FrameRate::Fps23976, " 00:01:00:12 ").start_frame(),
+            Some(1452)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_start_timecode() {
+        for timecode in [
+            "",
+            "01:00:00",
+            "01:00:00:00:00",
+            "aa:00:00:00",
+            "00:60:00:00",
+            "00:01:00;12",
+            "00;01;00;12",
+        ] {
+            assert_eq!(settings(FrameRate::Fps30, timecode).start_frame(), None);
+        }
+        // Frames must stay below the timebase
+        assert_eq!(
+            settings(FrameRate::Fps30, "00:00:00:30").start_frame(),
+            None
+        );
+        assert_eq!(
+            settings(FrameRate::Fps2997, "00:00:00:30").start_frame(),
+            None
+        );
+        assert_eq!(
+            settings(FrameRate::Fps60, "00:00:00:59").start_frame(),
+            Some(59)
+        );
+    }
+
+    #[test]
+    fn timecode_rolls_over_at_23_976() {
+        let export = export(FrameRate::Fps23976);
+        assert_eq!(export.timecode(23), "00:00:00:23");
+        assert_eq!(export.timecode(24), "00:00:01:00");
+        assert_eq!(export.timecode(24 * 3600 - 1), "00:59:59:23");
+        assert_eq!(export.timecode(24 * 3600), "01:00:00:00");
+    }
+
+    #[test]
+    fn timecode_rolls_over_at_29_97() {
+        let export = export(FrameRate::Fps2997);
+        assert_eq!(export.timecode(29), "00:00:00:29");
+        assert_eq!(export.timecode(30), "00:00:01:00");
+        assert_eq!(export.timecode(30 * 60 - 1), "00:00:59:29");
+        assert_eq!(export.timecode(30 * 60), "00:01:00:00");
+    }
+
+    #[test]
+    fn timecode_rolls_over_at_60() {
+        let export = export(FrameRate::Fps60);
+        assert_eq!(export.timecode(59), "00:00:00:59");
+        assert_eq!(export.timecode(60), "00:00:01:00");
+        assert_eq!(export.timecode(60 * 3600 - 1), "00:59:59:59");
+        assert_eq!(export.timecode(60 * 3600), "01:00:00:00");
+    }
+
+    #[test]
+    fn fcpxml_times_are_rational() {
+        assert_eq!(export(FrameRate::Fps23976).fcpxml_time(24), "24024/24000s");
+        assert_eq!(export(FrameRate::Fps2997).fcpxml_time(1), "1001/30000s");
+        assert_eq!(export(FrameRate::Fps30).fcpxml_time(30), "3000/3000s");
+        assert_eq!(export(FrameRate::Fps60).fcpxml_time(0), "0/6000s");
+    }
+
+    #[test]
+    fn refuses_sessions_without_a_recording() {
+        let settings = MarkerSettings::default();
+        assert!(MarkerExport::new(&SessionLog::new(), &settings).is_err());
+        assert!(MarkerExport::new(&SessionLog::for_recording(), &settings).is_ok());
+    }
+}
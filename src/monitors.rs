@@ -0,0 +1,551 @@
+use egui::Context;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::sync::mpsc::Receiver;
+
+// Pixels as the hardware sees them, or scaled down by the monitor's scale
+// factor as toolkits lay out windows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CoordinateSpace {
+    #[default]
+    Physical,
+    Logical,
+}
+
+impl CoordinateSpace {
+    // Space of the virtual desktop on this backend: pixels on X11 and for a
+    // per monitor DPI aware Windows process, points (CGEvent locations) on
+    // macOS. device_query reads the cursor and enigo moves it in this same
+    // space, so restores need no conversion between the two. Positions keep
+    // their space and scale anyway, for showing logical coordinates and for
+    // settings carried over from another platform.
+    pub fn desktop() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::Logical
+        } else {
+            Self::Physical
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Physical => "physical pixels",
+            Self::Logical => "logical pixels",
+        }
+    }
+
+    // Convert a point in this space to physical pixels
+    pub fn to_physical(self, x: i32, y: i32, scale_factor: f32) -> (i32, i32) {
+        match self {
+            Self::Physical => (x, y),
+            Self::Logical => (
+                (x as f32 * scale_factor).round() as i32,
+                (y as f32 * scale_factor).round() as i32,
+            ),
+        }
+    }
+
+    // Convert a point in physical pixels to this space
+    pub fn convert_from_physical(self, x: i32, y: i32, scale_factor: f32) -> (i32, i32) {
+        match self {
+            Self::Physical => (x, y),
+            Self::Logical => (
+                (x as f32 / scale_factor.max(0.1)).round() as i32,
+                (y as f32 / scale_factor.max(0.1)).round() as i32,
+            ),
+        }
+    }
+}
+
+// A connected monitor and where it sits on the virtual desktop
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Monitor {
+    // Connector name, e.g. "eDP-1" or "HDMI-1"
+    pub name: String,
+    // Manufacturer, product code and serial from the EDID, which stays the
+    // same when the monitor moves to another connector
+    pub edid: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+impl Monitor {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && i64::from(x) < i64::from(self.x) + i64::from(self.width)
+            && i64::from(y) < i64::from(self.y) + i64::from(self.height)
+    }
+
+    // Whether this is the same physical monitor as another one
+    pub fn is_same(&self, other: &Monitor) -> bool {
+        match (&self.edid, &other.edid) {
+            (Some(edid), Some(other_edid)) => edid == other_edid,
+            _ => self.name == other.name,
+        }
+    }
+
+    // Closest point on this monitor
+    pub fn clamp(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            x.clamp(self.x, self.x + self.width.max(1) as i32 - 1),
+            y.clamp(self.y, self.y + self.height.max(1) as i32 - 1),
+        )
+    }
+
+    // Point at a fraction of the monitor's width and height
+    pub fn at_fraction(&self, fraction_x: f64, fraction_y: f64) -> (i32, i32) {
+        self.clamp(
+            self.x + (fraction_x * f64::from(self.width)).round() as i32,
+            self.y + (fraction_y * f64::from(self.height)).round() as i32,
+        )
+    }
+
+    // Squared distance from a point to this monitor, 0 inside it
+    fn distance_squared(&self, x: i32, y: i32) -> i64 {
+        let (clamped_x, clamped_y) = self.clamp(x, y);
+        let dx = i64::from(x - clamped_x);
+        let dy = i64::from(y - clamped_y);
+        dx * dx + dy * dy
+    }
+
+    pub fn label(&self) -> String {
+        format!(
+            "{} ({}×{}, {}x)",
+            self.name, self.width, self.height, self.scale_factor
+        )
+    }
+}
+
+// Where a position was on the monitor it was saved on
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MonitorPosition {
+    pub monitor: Monitor,
+    // Offset from the monitor's top left corner
+    pub x: i32,
+    pub y: i32,
+}
+
+impl MonitorPosition {
+    // Whether the monitor this position was saved on is still connected
+    pub fn is_connected(&self, monitors: &[Monitor]) -> bool {
+        monitors
+            .iter()
+            .any(|monitor| monitor.is_same(&self.monitor))
+    }
+
+    // The monitor position of a point on the virtual desktop in physical
+    // pixels, if any monitor contains it
+    pub fn locate(monitors: &[Monitor], x: i32, y: i32) -> Option<Self> {
+        let monitor = at(monitors, x, y)?;
+        Some(Self {
+            monitor: monitor.clone(),
+            x: x - monitor.x,
+            y: y - monitor.y,
+        })
+    }
+
+    // Position as a fraction of the monitor's width and height
+    pub fn fraction(&self) -> (f64, f64) {
+        (
+            f64::from(self.x) / f64::from(self.monitor.width.max(1)),
+            f64::from(self.y) / f64::from(self.monitor.height.max(1)),
+        )
+    }
+
+    // Where this position is now, scaled to the monitor's current resolution,
+    // or None if the monitor is gone
+    pub fn remap(&self, monitors: &[Monitor]) -> Option<(i32, i32)> {
+        let monitor = monitors
+            .iter()
+            .find(|monitor| monitor.is_same(&self.monitor))?;
+        let (fraction_x, fraction_y) = self.fraction();
+        Some(monitor.at_fraction(fraction_x, fraction_y))
+    }
+
+    // Same fraction of the best matching monitor, so positions carry over to
+    // other resolutions and setups
+    pub fn remap_relative(&self, monitors: &[Monitor]) -> Option<(i32, i32)> {
+        let monitor = find_match(monitors, &self.monitor.name, self.monitor.edid.as_deref())?;
+        let (fraction_x, fraction_y) = self.fraction();
+        Some(monitor.at_fraction(fraction_x, fraction_y))
+    }
+}
+
+// The monitor standing in for a saved one: the same EDID, else the same
+// connector, else the primary monitor
+pub fn find_match<'a>(
+    monitors: &'a [Monitor],
+    name: &str,
+    edid: Option<&str>,
+) -> Option<&'a Monitor> {
+    edid.and_then(|edid| {
+        monitors
+            .iter()
+            .find(|monitor| monitor.edid.as_deref() == Some(edid))
+    })
+    .or_else(|| monitors.iter().find(|monitor| monitor.name == name))
+    .or_else(|| monitors.iter().find(|monitor| monitor.primary))
+    .or_else(|| monitors.first())
+}
+
+// Monitor containing a point given in physical pixels
+pub fn at(monitors: &[Monitor], x: i32, y: i32) -> Option<&Monitor> {
+    monitors.iter().find(|monitor| monitor.contains(x, y))
+}
+
+// Nearest point to x, y within the bounding box of all monitors. Monitors of
+// different sizes leave gaps in the box, a point there goes to the nearest
+// monitor instead.
+pub fn clamp_to_desktop(monitors: &[Monitor], x: i32, y: i32) -> Option<(i32, i32)> {
+    let left = monitors.iter().map(|monitor| monitor.x).min()?;
+    let top = monitors.iter().map(|monitor| monitor.y).min()?;
+    let right = monitors
+        .iter()
+        .map(|monitor| monitor.x + monitor.width.max(1) as i32 - 1)
+        .max()?;
+    let bottom = monitors
+        .iter()
+        .map(|monitor| monitor.y + monitor.height.max(1) as i32 - 1)
+        .max()?;
+    let (x, y) = (x.clamp(left, right), y.clamp(top, bottom));
+    if at(monitors, x, y).is_some() {
+        Some((x, y))
+    } else {
+        clamp_to_nearest(monitors, x, y)
+    }
+}
+
+// Nearest point to x, y on any monitor
+pub fn clamp_to_nearest(monitors: &[Monitor], x: i32, y: i32) -> Option<(i32, i32)> {
+    monitors
+        .iter()
+        .min_by_key(|monitor| monitor.distance_squared(x, y))
+        .map(|monitor| monitor.clamp(x, y))
+}
+
+// Monitors connected right now. Empty when they cannot be queried and on
+// macOS, positions are then used as they are.
+pub fn current() -> Vec<Monitor> {
+    #[cfg(target_os = "linux")]
+    {
+        x11::monitors().unwrap_or_else(|err| {
+            eprintln!("Could not query monitors: {}", err);
+            Vec::new()
+        })
+    }
+
+    #[cfg(windows)]
+    {
+        win32::monitors()
+    }
+
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        Vec::new()
+    }
+}
+
+// Notices monitors being added, removed or rearranged while running
+pub struct MonitorWatcher {
+    receiver: Receiver<Vec<Monitor>>,
+}
+
+impl MonitorWatcher {
+    pub fn new(ctx: Context) -> Result<Self, Box<dyn Error>> {
+        #[cfg(target_os = "linux")]
+        {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            x11::watch(sender, ctx)?;
+            Ok(Self { receiver })
+        }
+
+        #[cfg(windows)]
+        {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            win32::watch(sender, ctx);
+            Ok(Self { receiver })
+        }
+
+        #[cfg(not(any(target_os = "linux", windows)))]
+        {
+            let _ = ctx;
+            Err("Monitor hotplug detection is only supported on Linux and Windows".into())
+        }
+    }
+
+    // The new layout if monitors changed since the last call
+    pub fn poll(&self) -> Option<Vec<Monitor>> {
+        self.receiver.try_iter().last()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use egui::Context;
+    use std::cell::RefCell;
+    use std::error::Error;
+    use std::sync::mpsc::Sender;
+    use std::thread;
+    use std::time::Duration;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::Event;
+    use x11rb::protocol::randr::{ConnectionExt as _, NotifyMask, Output};
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, Window};
+    use x11rb::rust_connection::RustConnection;
+
+    use super::Monitor;
+
+    // Layout changes come in bursts, wait for the burst to end
+    const SETTLE_TIME: Duration = Duration::from_millis(300);
+
+    thread_local! {
+        // Monitors are queried on every save, keep a connection per thread
+        static CONNECTION: RefCell<Option<(RustConnection, Window)>> =
+            const { RefCell::new(None) };
+    }
+
+    pub fn monitors() -> Result<Vec<Monitor>, Box<dyn Error>> {
+        CONNECTION.with_borrow_mut(|cached| {
+            if let Some((conn, root)) = cached.as_ref()
+                && let Ok(monitors) = query(conn, *root)
+            {
+                return Ok(monitors);
+            }
+
+            // First query on this thread, or the connection broke
+            let (conn, screen_num) = x11rb::connect(None)?;
+            let root = conn.setup().roots[screen_num].root;
+            conn.randr_query_version(1, 5)?.reply()?;
+            let monitors = query(&conn, root)?;
+            *cached = Some((conn, root));
+            Ok(monitors)
+        })
+    }
+
+    // Listen for RandR change events and send the new layout after each one
+    pub fn watch(sender: Sender<Vec<Monitor>>, ctx: Context) -> Result<(), Box<dyn Error>> {
+        let (conn, screen_num) = x11rb::connect(None)?;
+        let root = conn.setup().roots[screen_num].root;
+        conn.randr_query_version(1, 5)?.reply()?;
+        conn.randr_select_input(
+            root,
+            NotifyMask::SCREEN_CHANGE | NotifyMask::CRTC_CHANGE | NotifyMask::OUTPUT_CHANGE,
+        )?
+        .check()?;
+
+        thread::spawn(move || {
+            while let Ok(event) = conn.wait_for_event() {
+                if !matches!(
+                    event,
+                    Event::RandrScreenChangeNotify(_) | Event::RandrNotify(_)
+                ) {
+                    continue;
+                }
+
+                thread::sleep(SETTLE_TIME);
+                while let Ok(Some(_)) = conn.poll_for_event() {}
+
+                match query(&conn, root) {
+                    Ok(monitors) => {
+                        if sender.send(monitors).is_err() {
+                            break;
+                        }
+                        ctx.request_repaint();
+                    }
+                    Err(err) => eprintln!("Could not query monitors: {}", err),
+                }
+            }
+        });
+        Ok(())
+    }
+
+    // Monitors from RandR 1.5, which also covers monitors made of several
+    // outputs
+    fn query(conn: &impl Connection, root: Window) -> Result<Vec<Monitor>, Box<dyn Error>> {
+        let scale_factor = scale_factor(conn, root).unwrap_or(1.0);
+        let edid_atom = conn.intern_atom(true, b"EDID")?.reply()?.atom;
+
+        let reply = conn.randr_get_monitors(root, true)?.reply()?;
+        let mut monitors = Vec::new();
+        for monitor in reply.monitors {
+            let name = conn.get_atom_name(monitor.name)?.reply()?.name;
+            let edid = monitor
+                .outputs
+                .first()
+                .and_then(|&output| edid(conn, output, edid_atom));
+
+            monitors.push(Monitor {
+                name: String::from_utf8_lossy(&name).into_owned(),
+                edid,
+                x: i32::from(monitor.x),
+                y: i32::from(monitor.y),
+                width: u32::from(monitor.width),
+                height: u32::from(monitor.height),
+                scale_factor,
+                primary: monitor.primary,
+            });
+        }
+        Ok(monitors)
+    }
+
+    // X11 has one scale for all monitors, the Xft.dpi resource desktops set
+    fn scale_factor(conn: &impl Connection, root: Window) -> Option<f32> {
+        let reply = conn
+            .get_property(
+                false,
+                root,
+                AtomEnum::RESOURCE_MANAGER,
+                AtomEnum::STRING,
+                0,
+                u32::MAX,
+            )
+            .ok()?
+            .reply()
+            .ok()?;
+        let resources = String::from_utf8_lossy(&reply.value);
+        let dpi: f32 = resources
+            .lines()
+            .find_map(|line| line.strip_prefix("Xft.dpi:"))?
+            .trim()
+            .parse()
+            .ok()?;
+        Some(dpi / 96.0)
+    }
+
+    // Stable identity from the EDID, e.g. "DEL-A0B1-12345678"
+    fn edid(conn: &impl Connection, output: Output, edid_atom: u32) -> Option<String> {
+        if edid_atom == 0 {
+            return None;
+        }
+        let data = conn
+            .randr_get_output_property(output, edid_atom, AtomEnum::ANY, 0, 64, false, false)
+            .ok()?
+            .reply()
+            .ok()?
+            .data;
+        if data.len() < 16 || data[..8] != [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00] {
+            return None;
+        }
+
+        // Three letters of five bits each, 1 is 'A'
+        let vendor = u16::from_be_bytes([data[8], data[9]]);
+        let letter = |shift: u16| char::from(b'@' + ((vendor >> shift) & 0x1f) as u8);
+        let product = u16::from_le_bytes([data[10], data[11]]);
+        let serial = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
+        Some(format!(
+            "{}{}{}-{:04X}-{:08X}",
+            letter(10),
+            letter(5),
+            letter(0),
+            product,
+            serial
+        ))
+    }
+}
+
+#[cfg(windows)]
+mod win32 {
+    use egui::Context;
+    use std::sync::mpsc::Sender;
+    use std::thread;
+    use std::time::Duration;
+    use windows_sys::Win32::Foundation::{BOOL, LPARAM, RECT, TRUE};
+    use windows_sys::Win32::Graphics::Gdi::{
+        EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW,
+    };
+    use windows_sys::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+    use windows_sys::Win32::UI::WindowsAndMessaging::MONITORINFOF_PRIMARY;
+
+    use super::Monitor;
+
+    // Display changes are only broadcast to windows, poll for them instead
+    const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    // Monitors in physical pixels, winit makes the process per monitor DPI
+    // aware before we get here
+    pub fn monitors() -> Vec<Monitor> {
+        let mut monitors: Vec<Monitor> = Vec::new();
+        // SAFETY: the callback only runs during the call and gets the vector
+        // as its data
+        unsafe {
+            EnumDisplayMonitors(
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                Some(add_monitor),
+                &mut monitors as *mut Vec<Monitor> as LPARAM,
+            );
+        }
+        monitors
+    }
+
+    // Send the new layout whenever it differs from the last one
+    pub fn watch(sender: Sender<Vec<Monitor>>, ctx: Context) {
+        thread::spawn(move || {
+            let mut last = monitors();
+            loop {
+                thread::sleep(POLL_INTERVAL);
+                let monitors = monitors();
+                if monitors == last {
+                    continue;
+                }
+                if sender.send(monitors.clone()).is_err() {
+                    break;
+                }
+                ctx.request_repaint();
+                last = monitors;
+            }
+        });
+    }
+
+    unsafe extern "system" fn add_monitor(
+        handle: HMONITOR,
+        _hdc: HDC,
+        _rect: *mut RECT,
+        data: LPARAM,
+    ) -> BOOL {
+        // SAFETY: data is the vector monitors() passed in
+        let monitors = unsafe { &mut *(data as *mut Vec<Monitor>) };
+
+        // SAFETY: MONITORINFOEXW is plain data, cbSize tells Windows to fill
+        // in the device name too
+        let mut info: MONITORINFOEXW = unsafe { std::mem::zeroed() };
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if unsafe { GetMonitorInfoW(handle, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO) }
+            == 0
+        {
+            return TRUE;
+        }
+
+        // The scale set in the display settings for this monitor
+        let (mut dpi_x, mut dpi_y) = (0, 0);
+        let result = unsafe { GetDpiForMonitor(handle, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) };
+        let scale_factor = if result == 0 && dpi_x > 0 {
+            dpi_x as f32 / 96.0
+        } else {
+            1.0
+        };
+
+        // Device names like \\.\DISPLAY1 name the output like a connector, the
+        // EDID would take SetupAPI and the registry
+        let device = &info.szDevice;
+        let name_len = device.iter().position(|&c| c == 0).unwrap_or(device.len());
+        let rect = info.monitorInfo.rcMonitor;
+        monitors.push(Monitor {
+            name: String::from_utf16_lossy(&device[..name_len]),
+            edid: None,
+            x: rect.left,
+            y: rect.top,
+            width: (rect.right - rect.left).max(0) as u32,
+            height: (rect.bottom - rect.top).max(0) as u32,
+            scale_factor,
+            primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+        });
+        TRUE
+    }
+}
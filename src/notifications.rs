@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Events that can raise a desktop notification
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NotificationEvent {
+    PositionSaved,
+    RestoreFailed,
+    HotkeyError,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub position_saved: bool,
+    pub restore_failed: bool,
+    pub hotkey_error: bool,
+    // Minimum time between two notifications for the same event
+    pub min_interval_secs: f32,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            position_saved: false,
+            restore_failed: true,
+            hotkey_error: true,
+            min_interval_secs: 10.0,
+        }
+    }
+}
+
+impl NotificationSettings {
+    fn is_enabled(&self, event: NotificationEvent) -> bool {
+        match event {
+            NotificationEvent::PositionSaved => self.position_saved,
+            NotificationEvent::RestoreFailed => self.restore_failed,
+            NotificationEvent::HotkeyError => self.hotkey_error,
+        }
+    }
+}
+
+// Only the D-Bus backend reads the fields
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct Notification {
+    event: NotificationEvent,
+    summary: String,
+    body: String,
+}
+
+// Sends desktop notifications over D-Bus (org.freedesktop.Notifications)
+// from a background thread so a slow notification daemon never blocks the UI
+pub struct Notifier {
+    sender: Sender<Notification>,
+    last_sent: HashMap<NotificationEvent, Instant>,
+}
+
+impl Notifier {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel::<Notification>();
+
+        thread::spawn(move || {
+            let mut backend = Backend::connect();
+            while let Ok(notification) = receiver.recv() {
+                backend.send(&notification);
+            }
+        });
+
+        Self {
+            sender,
+            last_sent: HashMap::new(),
+        }
+    }
+
+    // Send a notification if the event is enabled and not rate limited
+    pub fn notify(
+        &mut self,
+        settings: &NotificationSettings,
+        event: NotificationEvent,
+        summary: &str,
+        body: &str,
+    ) {
+        if !settings.is_enabled(event) {
+            return;
+        }
+
+        let min_interval =
+            Duration::try_from_secs_f32(settings.min_interval_secs).unwrap_or_default();
+        if let Some(last) = self.last_sent.get(&event)
+            && last.elapsed() < min_interval
+        {
+            return;
+        }
+        self.last_sent.insert(event, Instant::now());
+
+        let _ = self.sender.send(Notification {
+            event,
+            summary: summary.to_string(),
+            body: body.to_string(),
+        });
+    }
+}
+
+#[cfg(target_os = "linux")]
+struct Backend {
+    connection: Option<zbus::blocking::Connection>,
+    // Replace the previous notification of the same event instead of stacking
+    notification_ids: HashMap<NotificationEvent, u32>,
+}
+
+#[cfg(target_os = "linux")]
+impl Backend {
+    fn connect() -> Self {
+        let connection = zbus::blocking::Connection::session()
+            .map_err(|err| eprintln!("Desktop notifications unavailable: {}", err))
+            .ok();
+
+        Self {
+            connection,
+            notification_ids: HashMap::new(),
+        }
+    }
+
+    fn send(&mut self, notification: &Notification) {
+        use zbus::zvariant::Value;
+
+        let Some(connection) = &self.connection else {
+            return;
+        };
+
+        let replaces_id = self
+            .notification_ids
+            .get(&notification.event)
+            .copied()
+            .unwrap_or(0);
+        let actions: Vec<&str> = Vec::new();
+        let hints: HashMap<&str, Value> = HashMap::new();
+        let expire_timeout: i32 = -1;
+
+        let reply = connection.call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                crate::config::APP_NAME,
+                replaces_id,
+                "input-mouse",
+                notification.summary.as_str(),
+                notification.body.as_str(),
+                actions,
+                hints,
+                expire_timeout,
+            ),
+        );
+
+        match reply.and_then(|message| message.body().deserialize::<u32>()) {
+            Ok(id) => {
+                self.notification_ids.insert(notification.event, id);
+            }
+            Err(err) => eprintln!("Failed to send notification: {}", err),
+        }
+    }
+}
+
+// Other platforms have no notification backend yet
+#[cfg(not(target_os = "linux"))]
+struct Backend;
+
+#[cfg(not(target_os = "linux"))]
+impl Backend {
+    fn connect() -> Self {
+        Self
+    }
+
+    fn send(&mut self, _notification: &Notification) {}
+}
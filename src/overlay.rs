@@ -0,0 +1,299 @@
+use device_query::{DeviceQuery, DeviceState, Keycode};
+use egui::{Color32, Context, Pos2, Stroke, Vec2, ViewportBuilder, ViewportId, WindowLevel};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::tracker::SavedPosition;
+
+// Size of each marker window in points
+const MARKER_WINDOW_SIZE: f32 = 40.0;
+const MARKER_COLOR: Color32 = Color32::from_rgb(76, 175, 80);
+const OUTLINE_COLOR: Color32 = Color32::from_rgba_premultiplied(0, 0, 0, 160);
+
+// When the markers are visible
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverlayMode {
+    Always,
+    WhileModifierHeld,
+    AfterSave,
+}
+
+impl OverlayMode {
+    pub const ALL: [Self; 3] = [Self::Always, Self::WhileModifierHeld, Self::AfterSave];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Always => "Always",
+            Self::WhileModifierHeld => "While modifier is held",
+            Self::AfterSave => "For a few seconds after saving",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverlayModifier {
+    Ctrl,
+    Shift,
+    Alt,
+    Super,
+}
+
+impl OverlayModifier {
+    pub const ALL: [Self; 4] = [Self::Ctrl, Self::Shift, Self::Alt, Self::Super];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ctrl => "Ctrl",
+            Self::Shift => "Shift",
+            Self::Alt => "Alt",
+            Self::Super => "Super",
+        }
+    }
+
+    fn keys(self) -> &'static [Keycode] {
+        match self {
+            Self::Ctrl => &[Keycode::LControl, Keycode::RControl],
+            Self::Shift => &[Keycode::LShift, Keycode::RShift],
+            Self::Alt => &[
+                Keycode::LAlt,
+                Keycode::RAlt,
+                Keycode::LOption,
+                Keycode::ROption,
+            ],
+            Self::Super => &[
+                Keycode::LMeta,
+                Keycode::RMeta,
+                Keycode::Command,
+                Keycode::RCommand,
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarkerStyle {
+    Crosshair,
+    Ring,
+}
+
+impl MarkerStyle {
+    pub const ALL: [Self; 2] = [Self::Crosshair, Self::Ring];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Crosshair => "Crosshair",
+            Self::Ring => "Ring",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    pub enabled: bool,
+    pub mode: OverlayMode,
+    pub modifier: OverlayModifier,
+    pub duration_secs: f32,
+    pub style: MarkerStyle,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: OverlayMode::Always,
+            modifier: OverlayModifier::Ctrl,
+            duration_secs: 3.0,
+            style: MarkerStyle::Crosshair,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PulseSettings {
+    pub enabled: bool,
+    pub hide_while_recording: bool,
+    pub color: [u8; 3],
+    pub radius: f32,
+    pub duration_secs: f32,
+}
+
+impl Default for PulseSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hide_while_recording: false,
+            color: [76, 175, 80],
+            radius: 40.0,
+            duration_secs: 0.6,
+        }
+    }
+}
+
+// Transparent, click-through, always-on-top windows marking saved positions
+pub struct MarkerOverlay {
+    device_state: DeviceState,
+}
+
+impl MarkerOverlay {
+    pub fn new() -> Self {
+        Self {
+            device_state: DeviceState::new(),
+        }
+    }
+
+    // Show a marker at each position that should currently be visible. Markers
+    // not shown in a frame are closed by egui.
+    pub fn show(&self, ctx: &Context, settings: &OverlaySettings, positions: &[SavedPosition]) {
+        if !settings.enabled {
+            return;
+        }
+
+        if settings.mode == OverlayMode::WhileModifierHeld && !self.modifier_held(settings.modifier)
+        {
+            return;
+        }
+
+        // Saved positions are in physical pixels, viewports are placed in points
+        let pixels_per_point = ctx
+            .input(|i| i.viewport().native_pixels_per_point)
+            .unwrap_or(1.0);
+
+        for (index, position) in positions.iter().enumerate() {
+            if settings.mode == OverlayMode::AfterSave {
+                let recent = position
+                    .timestamp
+                    .elapsed()
+                    .is_ok_and(|elapsed| elapsed.as_secs_f32() < settings.duration_secs);
+                if !recent {
+                    continue;
+                }
+            }
+
+            let (x, y) = position.physical();
+            let center = Pos2::new(x as f32, y as f32) / pixels_per_point;
+            Self::show_marker(ctx, index, center, settings.style);
+        }
+    }
+
+    fn modifier_held(&self, modifier: OverlayModifier) -> bool {
+        let keys = self.device_state.get_keys();
+        modifier.keys().iter().any(|key| keys.contains(key))
+    }
+
+    fn show_marker(ctx: &Context, index: usize, center: Pos2, style: MarkerStyle) {
+        ctx.show_viewport_immediate(
+            ViewportId::from_hash_of(("marker_overlay", index)),
+            overlay_viewport("MouseMinder Marker", center, MARKER_WINDOW_SIZE),
+            |ctx, _class| {
+                egui::CentralPanel::default()
+                    .frame(egui::Frame::NONE)
+                    .show(ctx, |ui| {
+                        paint_marker(ui.painter(), ui.max_rect().center(), style);
+                    });
+            },
+        );
+    }
+}
+
+// Expanding ring drawn around the cursor after a restore
+pub struct RestorePulse {
+    active: Option<(Instant, Pos2)>,
+}
+
+impl RestorePulse {
+    pub fn new() -> Self {
+        Self { active: None }
+    }
+
+    // Start the animation at a position in physical pixels
+    pub fn trigger(&mut self, x: i32, y: i32) {
+        self.active = Some((Instant::now(), Pos2::new(x as f32, y as f32)));
+    }
+
+    pub fn show(&mut self, ctx: &Context, settings: &PulseSettings, recording: bool) {
+        let Some((started, position)) = self.active else {
+            return;
+        };
+
+        let duration =
+            Duration::try_from_secs_f32(settings.duration_secs.max(0.05)).unwrap_or_default();
+        let elapsed = started.elapsed();
+        if !settings.enabled || (recording && settings.hide_while_recording) || elapsed >= duration
+        {
+            self.active = None;
+            return;
+        }
+
+        let pixels_per_point = ctx
+            .input(|i| i.viewport().native_pixels_per_point)
+            .unwrap_or(1.0);
+        let progress = elapsed.as_secs_f32() / duration.as_secs_f32();
+        let [r, g, b] = settings.color;
+        let radius = settings.radius;
+
+        ctx.show_viewport_immediate(
+            ViewportId::from_hash_of("restore_pulse"),
+            overlay_viewport(
+                "MouseMinder Pulse",
+                position / pixels_per_point,
+                radius * 2.0 + 8.0,
+            ),
+            |ctx, _class| {
+                egui::CentralPanel::default()
+                    .frame(egui::Frame::NONE)
+                    .show(ctx, |ui| {
+                        // Grow from the cursor outwards while fading away
+                        let color = Color32::from_rgb(r, g, b).gamma_multiply(1.0 - progress);
+                        let ring_radius = 4.0 + (radius - 4.0) * progress;
+                        ui.painter().circle_stroke(
+                            ui.max_rect().center(),
+                            ring_radius,
+                            Stroke::new(3.0, color),
+                        );
+                    });
+            },
+        );
+
+        // Keep animating until the pulse is done
+        ctx.request_repaint();
+    }
+}
+
+// Borderless, transparent, click-through window centered on a point
+fn overlay_viewport(title: &str, center: Pos2, size: f32) -> ViewportBuilder {
+    ViewportBuilder::default()
+        .with_title(title)
+        .with_position(center - Vec2::splat(size / 2.0))
+        .with_inner_size([size, size])
+        .with_decorations(false)
+        .with_resizable(false)
+        .with_transparent(true)
+        .with_mouse_passthrough(true)
+        .with_window_level(WindowLevel::AlwaysOnTop)
+        .with_taskbar(false)
+        .with_active(false)
+}
+
+fn paint_marker(painter: &egui::Painter, center: Pos2, style: MarkerStyle) {
+    match style {
+        MarkerStyle::Crosshair => {
+            for (from, to) in [
+                (Vec2::new(-14.0, 0.0), Vec2::new(-4.0, 0.0)),
+                (Vec2::new(4.0, 0.0), Vec2::new(14.0, 0.0)),
+                (Vec2::new(0.0, -14.0), Vec2::new(0.0, -4.0)),
+                (Vec2::new(0.0, 4.0), Vec2::new(0.0, 14.0)),
+            ] {
+                let segment = [center + from, center + to];
+                painter.line_segment(segment, Stroke::new(4.0, OUTLINE_COLOR));
+                painter.line_segment(segment, Stroke::new(2.0, MARKER_COLOR));
+            }
+            painter.circle_filled(center, 2.0, MARKER_COLOR);
+        }
+        MarkerStyle::Ring => {
+            painter.circle_stroke(center, 12.0, Stroke::new(5.0, OUTLINE_COLOR));
+            painter.circle_stroke(center, 12.0, Stroke::new(3.0, MARKER_COLOR));
+        }
+    }
+}
@@ -0,0 +1,435 @@
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::export::rfc3339;
+
+// Mouse buttons in PathSample::buttons
+pub const BUTTON_LEFT: u8 = 1 << 0;
+pub const BUTTON_RIGHT: u8 = 1 << 1;
+pub const BUTTON_MIDDLE: u8 = 1 << 2;
+
+// File extension of the binary path format
+pub const FILE_EXTENSION: &str = "mmpath";
+
+// Binary format: magic, version, start time as unix microseconds and the
+// sample count, followed by one fixed size little endian record per sample
+const MAGIC: &[u8; 6] = b"MMPATH";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 6 + 1 + 8 + 4;
+const RECORD_LEN: usize = 4 + 4 + 4 + 1;
+
+// Heatmap cell size of the SVG preview in pixels
+const HEATMAP_CELL: i32 = 32;
+
+// Cursor position and button state at one point of a recording
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct PathSample {
+    // Milliseconds since the recording started
+    pub offset_ms: u32,
+    pub x: i32,
+    pub y: i32,
+    pub buttons: u8,
+}
+
+impl PathSample {
+    pub fn is_pressed(&self, button: u8) -> bool {
+        self.buttons & button != 0
+    }
+}
+
+// A recorded cursor trajectory. Samples are only stored when the cursor moved
+// or a button changed, the cursor stays put until the next sample.
+#[derive(Clone, Debug)]
+pub struct CursorPath {
+    started_at: SystemTime,
+    samples: Vec<PathSample>,
+}
+
+// Collects samples from the tracking thread while a path is recorded
+pub struct PathRecorder {
+    started: Instant,
+    path: CursorPath,
+}
+
+impl PathRecorder {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            path: CursorPath {
+                started_at: SystemTime::now(),
+                samples: Vec::new(),
+            },
+        }
+    }
+
+    // Add a sample from device_query's mouse state if anything changed
+    pub fn sample(&mut self, coords: (i32, i32), button_pressed: &[bool]) {
+        let buttons = button_mask(button_pressed);
+        if let Some(last) = self.path.samples.last()
+            && (last.x, last.y, last.buttons) == (coords.0, coords.1, buttons)
+        {
+            return;
+        }
+
+        self.path.samples.push(PathSample {
+            offset_ms: self.started.elapsed().as_millis().min(u32::MAX as u128) as u32,
+            x: coords.0,
+            y: coords.1,
+            buttons,
+        });
+    }
+
+    pub fn finish(self) -> CursorPath {
+        self.path
+    }
+}
+
+// device_query reports buttons by platform number, X11 calls the middle
+// button 2 where Windows and macOS call it 3
+pub fn button_mask(button_pressed: &[bool]) -> u8 {
+    let (right, middle) = if cfg!(target_os = "linux") {
+        (3, 2)
+    } else {
+        (2, 3)
+    };
+    let pressed = |index: usize| button_pressed.get(index).copied().unwrap_or(false);
+
+    let mut buttons = 0;
+    if pressed(1) {
+        buttons |= BUTTON_LEFT;
+    }
+    if pressed(right) {
+        buttons |= BUTTON_RIGHT;
+    }
+    if pressed(middle) {
+        buttons |= BUTTON_MIDDLE;
+    }
+    buttons
+}
+
+// Names of the buttons in a mask, e.g. "left, right"
+pub fn button_names(buttons: u8) -> String {
+    [
+        (BUTTON_LEFT, "left"),
+        (BUTTON_RIGHT, "right"),
+        (BUTTON_MIDDLE, "middle"),
+    ]
+    .iter()
+    .filter(|(button, _)| buttons & button != 0)
+    .map(|(_, name)| *name)
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+impl CursorPath {
+    // Load the newest path file in the export folder. Files are named after
+    // the time they were saved, so the newest sorts last.
+    pub fn load_latest(dir: &str) -> io::Result<Option<Self>> {
+        let latest = fs::read_dir(Path::new(dir))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| {
+                file.extension()
+                    .is_some_and(|extension| extension == FILE_EXTENSION)
+            })
+            .max();
+
+        match latest {
+            Some(file) => Self::from_bytes(&fs::read(file)?).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn samples(&self) -> &[PathSample] {
+        &self.samples
+    }
+
+    pub fn duration(&self) -> Duration {
+        let last = self.samples.last().map_or(0, |sample| sample.offset_ms);
+        Duration::from_millis(u64::from(last))
+    }
+
+    // Number of button presses in the recording
+    pub fn click_count(&self) -> usize {
+        self.presses().count()
+    }
+
+    // Samples where a button went down, with the buttons that did
+    fn presses(&self) -> impl Iterator<Item = (&PathSample, u8)> + '_ {
+        let previous = std::iter::once(0).chain(self.samples.iter().map(|sample| sample.buttons));
+        self.samples
+            .iter()
+            .zip(previous)
+            .map(|(sample, previous)| (sample, sample.buttons & !previous))
+            .filter(|(_, pressed)| *pressed != 0)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let started_at = self
+            .started_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.samples.len() * RECORD_LEN);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&started_at.to_le_bytes());
+        bytes.extend_from_slice(&(self.samples.len() as u32).to_le_bytes());
+        for sample in &self.samples {
+            bytes.extend_from_slice(&sample.offset_ms.to_le_bytes());
+            bytes.extend_from_slice(&sample.x.to_le_bytes());
+            bytes.extend_from_slice(&sample.y.to_le_bytes());
+            bytes.push(sample.buttons);
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        if bytes.len() < HEADER_LEN || &bytes[..6] != MAGIC {
+            return Err(invalid("Not a cursor path file"));
+        }
+        if bytes[6] != VERSION {
+            return Err(invalid("Unsupported cursor path version"));
+        }
+
+        let started_at = u64::from_le_bytes(bytes[7..15].try_into().unwrap());
+        let count = u32::from_le_bytes(bytes[15..19].try_into().unwrap()) as usize;
+        let records = &bytes[HEADER_LEN..];
+        if records.len() != count * RECORD_LEN {
+            return Err(invalid("Cursor path file is truncated"));
+        }
+
+        let samples = records
+            .chunks_exact(RECORD_LEN)
+            .map(|record| PathSample {
+                offset_ms: u32::from_le_bytes(record[0..4].try_into().unwrap()),
+                x: i32::from_le_bytes(record[4..8].try_into().unwrap()),
+                y: i32::from_le_bytes(record[8..12].try_into().unwrap()),
+                buttons: record[12],
+            })
+            .collect();
+
+        Ok(Self {
+            started_at: UNIX_EPOCH + Duration::from_micros(started_at),
+            samples,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct Json<'a> {
+            started_at: String,
+            duration_ms: u32,
+            samples: &'a [PathSample],
+        }
+
+        serde_json::to_string_pretty(&Json {
+            started_at: rfc3339(self.started_at),
+            duration_ms: self.duration().as_millis() as u32,
+            samples: &self.samples,
+        })
+        .unwrap()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("offset_ms,x,y,left,right,middle\n");
+        for sample in &self.samples {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                sample.offset_ms,
+                sample.x,
+                sample.y,
+                u8::from(sample.is_pressed(BUTTON_LEFT)),
+                u8::from(sample.is_pressed(BUTTON_RIGHT)),
+                u8::from(sample.is_pressed(BUTTON_MIDDLE)),
+            ));
+        }
+        csv
+    }
+
+    // Preview of the path: a dwell time heatmap below the trajectory, with the
+    // start, the end and every click marked
+    pub fn to_svg(&self) -> String {
+        let Some(first) = self.samples.first() else {
+            return String::from(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"0\" height=\"0\"/>\n",
+            );
+        };
+        let last = self.samples.last().unwrap();
+
+        let padding = HEATMAP_CELL;
+        let min_x = self.samples.iter().map(|s| s.x).min().unwrap() - padding;
+        let min_y = self.samples.iter().map(|s| s.y).min().unwrap() - padding;
+        let max_x = self.samples.iter().map(|s| s.x).max().unwrap() + padding;
+        let max_y = self.samples.iter().map(|s| s.y).max().unwrap() + padding;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\">\n",
+            min_x,
+            min_y,
+            max_x - min_x,
+            max_y - min_y,
+            max_x - min_x,
+            max_y - min_y
+        );
+        svg.push_str(&format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#1e1e23\"/>\n",
+            min_x,
+            min_y,
+            max_x - min_x,
+            max_y - min_y
+        ));
+
+        // Time spent in each cell, until the next sample moved the cursor on
+        let mut dwell = std::collections::HashMap::<(i32, i32), u64>::new();
+        for pair in self.samples.windows(2) {
+            let cell = (
+                pair[0].x.div_euclid(HEATMAP_CELL),
+                pair[0].y.div_euclid(HEATMAP_CELL),
+            );
+            *dwell.entry(cell).or_default() += u64::from(pair[1].offset_ms - pair[0].offset_ms);
+        }
+        let max_dwell = dwell.values().copied().max().unwrap_or(0).max(1);
+        let mut cells: Vec<_> = dwell.into_iter().filter(|(_, ms)| *ms > 0).collect();
+        cells.sort();
+        svg.push_str("  <g fill=\"#ff9800\">\n");
+        for ((cell_x, cell_y), ms) in cells {
+            svg.push_str(&format!(
+                "    <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill-opacity=\"{:.3}\"/>\n",
+                cell_x * HEATMAP_CELL,
+                cell_y * HEATMAP_CELL,
+                HEATMAP_CELL,
+                HEATMAP_CELL,
+                0.1 + 0.7 * ms as f64 / max_dwell as f64
+            ));
+        }
+        svg.push_str("  </g>\n");
+
+        let points: Vec<String> = self
+            .samples
+            .iter()
+            .map(|sample| format!("{},{}", sample.x, sample.y))
+            .collect();
+        svg.push_str(&format!(
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"#dcdce6\" stroke-width=\"2\" stroke-linejoin=\"round\"/>\n",
+            points.join(" ")
+        ));
+
+        for (sample, pressed) in self.presses() {
+            let color = if pressed & BUTTON_LEFT != 0 {
+                "#2196f3"
+            } else {
+                "#e91e63"
+            };
+            svg.push_str(&format!(
+                "  <circle cx=\"{}\" cy=\"{}\" r=\"6\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+                sample.x, sample.y, color
+            ));
+        }
+
+        svg.push_str(&format!(
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"5\" fill=\"#4caf50\"/>\n",
+            first.x, first.y
+        ));
+        svg.push_str(&format!(
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"5\" fill=\"#ef5350\"/>\n",
+            last.x, last.y
+        ));
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path() -> CursorPath {
+        CursorPath {
+            started_at: UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456),
+            samples: vec![
+                PathSample {
+                    offset_ms: 0,
+                    x: 10,
+                    y: 20,
+                    buttons: 0,
+                },
+                PathSample {
+                    offset_ms: 16,
+                    x: -1920,
+                    y: 1080,
+                    buttons: BUTTON_LEFT | BUTTON_MIDDLE,
+                },
+                PathSample {
+                    offset_ms: u32::MAX,
+                    x: i32::MIN,
+                    y: i32::MAX,
+                    buttons: BUTTON_RIGHT,
+                },
+            ],
+        }
+    }
+
+    fn error_message(bytes: &[u8]) -> String {
+        CursorPath::from_bytes(bytes).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let path = path();
+        let bytes = path.to_bytes();
+        assert_eq!(bytes.len(), HEADER_LEN + 3 * RECORD_LEN);
+
+        let decoded = CursorPath::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.started_at, path.started_at);
+        assert_eq!(decoded.samples, path.samples);
+    }
+
+    #[test]
+    fn round_trips_an_empty_path() {
+        let path = CursorPath {
+            samples: Vec::new(),
+            ..path()
+        };
+        let decoded = CursorPath::from_bytes(&path.to_bytes()).unwrap();
+        assert!(decoded.samples.is_empty());
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = path().to_bytes();
+        bytes[0] = b'X';
+        assert_eq!(error_message(&bytes), "Not a cursor path file");
+        assert_eq!(error_message(b"MMPATH"), "Not a cursor path file");
+    }
+
+    #[test]
+    fn rejects_bad_version() {
+        let mut bytes = path().to_bytes();
+        bytes[6] = VERSION + 1;
+        assert_eq!(error_message(&bytes), "Unsupported cursor path version");
+    }
+
+    #[test]
+    fn rejects_truncated_records() {
+        let bytes = path().to_bytes();
+        assert_eq!(
+            error_message(&bytes[..bytes.len() - 1]),
+            "Cursor path file is truncated"
+        );
+        assert_eq!(
+            error_message(&bytes[..HEADER_LEN + RECORD_LEN]),
+            "Cursor path file is truncated"
+        );
+
+        // Trailing bytes past the last record are as wrong as missing ones
+        let mut bytes = bytes;
+        bytes.push(0);
+        assert_eq!(error_message(&bytes), "Cursor path file is truncated");
+    }
+}
@@ -0,0 +1,276 @@
+use device_query::{DeviceQuery, DeviceState};
+use enigo::{Button, Coordinate, Direction, Enigo, Mouse, Settings};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::path::{BUTTON_LEFT, BUTTON_MIDDLE, BUTTON_RIGHT, CursorPath, PathSample};
+
+// How far the cursor may be from where the replay put it before we assume
+// the user grabbed the mouse
+const TAKEOVER_DISTANCE: i32 = 4;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplaySettings {
+    // Playback speed, 2.0 replays twice as fast
+    pub speed: f32,
+    // Average each position with this many samples on either side
+    pub smoothing: u32,
+    pub replay_clicks: bool,
+    // Time to get ready before the cursor starts moving
+    pub start_delay_secs: f32,
+}
+
+impl Default for ReplaySettings {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            smoothing: 0,
+            replay_clicks: false,
+            start_delay_secs: 3.0,
+        }
+    }
+}
+
+// Where a replay is at
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplayState {
+    Waiting,
+    // Fraction of the path replayed so far
+    Playing(f32),
+    Finished,
+    Cancelled(String),
+    Failed(String),
+}
+
+impl ReplayState {
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Waiting | Self::Playing(_))
+    }
+}
+
+// A cursor path being re-performed through enigo on a background thread
+pub struct Replay {
+    cancel: Arc<AtomicBool>,
+    state: Arc<Mutex<ReplayState>>,
+}
+
+impl Replay {
+    pub fn start(path: &CursorPath, settings: &ReplaySettings) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let state = Arc::new(Mutex::new(ReplayState::Waiting));
+        let samples = smooth(path.samples(), settings.smoothing as usize);
+        let settings = settings.clone();
+
+        let thread_cancel = Arc::clone(&cancel);
+        let thread_state = Arc::clone(&state);
+        thread::spawn(move || {
+            let result = play(&samples, &settings, &thread_cancel, &thread_state);
+            *thread_state.lock().unwrap() = match result {
+                Ok(()) if thread_cancel.load(Ordering::Relaxed) => {
+                    ReplayState::Cancelled("Aborted".into())
+                }
+                Ok(()) => ReplayState::Finished,
+                Err(state) => state,
+            };
+        });
+
+        Self { cancel, state }
+    }
+
+    // Stop the replay as soon as possible
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn state(&self) -> ReplayState {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+impl Drop for Replay {
+    fn drop(&mut self) {
+        // Never leave a replay running without a way to stop it
+        self.cancel();
+    }
+}
+
+fn play(
+    samples: &[PathSample],
+    settings: &ReplaySettings,
+    cancel: &AtomicBool,
+    state: &Mutex<ReplayState>,
+) -> Result<(), ReplayState> {
+    let input_error = |err: enigo::InputError| ReplayState::Failed(err.to_string());
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|err| ReplayState::Failed(err.to_string()))?;
+    let device_state = DeviceState::new();
+
+    // Wait in small steps so an abort during the delay is quick
+    let delay = Duration::try_from_secs_f32(settings.start_delay_secs).unwrap_or_default();
+    let delay_end = Instant::now() + delay;
+    while Instant::now() < delay_end {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_millis(crate::config::POLL_INTERVAL_MS));
+    }
+
+    // Clamping lets NaN through
+    let speed = if settings.speed.is_nan() {
+        1.0
+    } else {
+        settings.speed.clamp(0.1, 10.0)
+    };
+    let total_ms = samples.last().map_or(0, |sample| sample.offset_ms).max(1);
+    let started = Instant::now();
+    // The last two positions we moved to, the newest may not be applied yet
+    let mut targets: Option<((i32, i32), (i32, i32))> = None;
+    let mut held = 0u8;
+
+    let result = (|| {
+        for sample in samples {
+            let due = Duration::from_secs_f32(sample.offset_ms as f32 / 1000.0 / speed);
+            while let Some(wait) = due.checked_sub(started.elapsed()) {
+                if cancel.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+                thread::sleep(wait.min(Duration::from_millis(crate::config::POLL_INTERVAL_MS)));
+            }
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+
+            // Hand control back as soon as the physical mouse moves
+            if let Some((previous, last)) = targets {
+                let (x, y) = device_state.get_mouse().coords;
+                let near = |(tx, ty): (i32, i32)| {
+                    (x - tx).abs() <= TAKEOVER_DISTANCE && (y - ty).abs() <= TAKEOVER_DISTANCE
+                };
+                if !near(previous) && !near(last) {
+                    return Err(ReplayState::Cancelled("The mouse was moved".into()));
+                }
+            }
+
+            enigo
+                .move_mouse(sample.x, sample.y, Coordinate::Abs)
+                .map_err(input_error)?;
+            let position = (sample.x, sample.y);
+            targets = Some(targets.map_or((position, position), |(_, last)| (last, position)));
+
+            if settings.replay_clicks {
+                for (button, enigo_button) in [
+                    (BUTTON_LEFT, Button::Left),
+                    (BUTTON_RIGHT, Button::Right),
+                    (BUTTON_MIDDLE, Button::Middle),
+                ] {
+                    let direction = match (held & button != 0, sample.is_pressed(button)) {
+                        (false, true) => Direction::Press,
+                        (true, false) => Direction::Release,
+                        _ => continue,
+                    };
+                    enigo.button(enigo_button, direction).map_err(input_error)?;
+                    held ^= button;
+                }
+            }
+
+            *state.lock().unwrap() =
+                ReplayState::Playing(sample.offset_ms as f32 / total_ms as f32);
+        }
+        Ok(())
+    })();
+
+    // Never leave a button pressed, however the replay ended
+    for (button, enigo_button) in [
+        (BUTTON_LEFT, Button::Left),
+        (BUTTON_RIGHT, Button::Right),
+        (BUTTON_MIDDLE, Button::Middle),
+    ] {
+        if held & button != 0 {
+            let _ = enigo.button(enigo_button, Direction::Release);
+        }
+    }
+
+    result
+}
+
+// Moving average over the neighbouring samples. Samples where a button
+// changes keep their exact position so clicks land where they were made.
+fn smooth(samples: &[PathSample], radius: usize) -> Vec<PathSample> {
+    if radius == 0 {
+        return samples.to_vec();
+    }
+
+    samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| {
+            let button_changed = index
+                .checked_sub(1)
+                .is_some_and(|previous| samples[previous].buttons != sample.buttons);
+            if button_changed {
+                return *sample;
+            }
+
+            let window =
+                &samples[index.saturating_sub(radius)..(index + radius + 1).min(samples.len())];
+            let count = window.len() as i64;
+            PathSample {
+                x: (window.iter().map(|s| i64::from(s.x)).sum::<i64>() / count) as i32,
+                y: (window.iter().map(|s| i64::from(s.y)).sum::<i64>() / count) as i32,
+                ..*sample
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Zigzag samples, where averaging moves every point, with the left button
+    // pressed at the fourth sample and released at the sixth
+    fn samples() -> Vec<PathSample> {
+        (0..7)
+            .map(|index| PathSample {
+                offset_ms: index * 10,
+                x: if index % 2 == 0 { 0 } else { 90 },
+                y: if index % 2 == 0 { 300 } else { 0 },
+                buttons: if (3..5).contains(&index) {
+                    BUTTON_LEFT
+                } else {
+                    0
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn smoothing_keeps_button_edges() {
+        let samples = samples();
+        let smoothed = smooth(&samples, 1);
+
+        // The press and the release land exactly where they were made
+        assert_eq!(smoothed[3], samples[3]);
+        assert_eq!(smoothed[5], samples[5]);
+
+        // Samples in between are averaged with their neighbours
+        assert_eq!((smoothed[1].x, smoothed[1].y), (30, 200));
+        assert_eq!((smoothed[4].x, smoothed[4].y), (60, 100));
+
+        // Timing and buttons are never touched
+        for (smoothed, sample) in smoothed.iter().zip(&samples) {
+            assert_eq!(smoothed.offset_ms, sample.offset_ms);
+            assert_eq!(smoothed.buttons, sample.buttons);
+        }
+    }
+
+    #[test]
+    fn smoothing_with_no_radius_keeps_samples() {
+        let samples = samples();
+        assert_eq!(smooth(&samples, 0), samples);
+    }
+}
@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::path::BUTTON_LEFT;
+use crate::tracker::SavedPosition;
+use crate::windows::WindowInfo;
+
+// A screen rectangle in physical pixels where idle saves are skipped
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExclusionZone {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl ExclusionZone {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        // Zones come from the settings file, keep huge ones from overflowing
+        x >= self.x
+            && y >= self.y
+            && i64::from(x) < i64::from(self.x) + i64::from(self.width)
+            && i64::from(y) < i64::from(self.y) + i64::from(self.height)
+    }
+}
+
+// Windows idle saves are skipped over. Both patterns are matched case
+// insensitively as contained text, an empty pattern matches any window.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowRule {
+    pub class: String,
+    pub title: String,
+}
+
+impl WindowRule {
+    pub fn matches(&self, window: &WindowInfo) -> bool {
+        let contains = |text: &str, pattern: &str| {
+            text.to_lowercase().contains(&pattern.trim().to_lowercase())
+        };
+        // A rule without patterns would match every window
+        let is_empty = self.class.trim().is_empty() && self.title.trim().is_empty();
+        !is_empty && contains(&window.class, &self.class) && contains(&window.title, &self.title)
+    }
+}
+
+// What makes the tracker save the cursor position
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaveTrigger {
+    // Where the cursor last rested
+    #[default]
+    Idle,
+    // Where a button was last pressed
+    Click,
+    Both,
+}
+
+impl SaveTrigger {
+    pub const ALL: [Self; 3] = [Self::Idle, Self::Click, Self::Both];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Idle => "The mouse rests",
+            Self::Click => "A button is clicked",
+            Self::Both => "Either",
+        }
+    }
+
+    pub fn on_idle(self) -> bool {
+        matches!(self, Self::Idle | Self::Both)
+    }
+
+    pub fn on_click(self) -> bool {
+        matches!(self, Self::Click | Self::Both)
+    }
+}
+
+// How typing affects idle saves. The mouse rests while typing, so where it
+// was parked often means nothing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyboardPolicy {
+    #[default]
+    Ignore,
+    // Do not save a rest during which keys were pressed
+    SuppressWhileTyping,
+    // Save only once the keyboard was idle for as long as the mouse
+    RequireIdle,
+}
+
+impl KeyboardPolicy {
+    pub const ALL: [Self; 3] = [Self::Ignore, Self::SuppressWhileTyping, Self::RequireIdle];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ignore => "Ignore the keyboard",
+            Self::SuppressWhileTyping => "No saves while typing",
+            Self::RequireIdle => "Keyboard idle too",
+        }
+    }
+}
+
+// The keyboard policy for one application, by window class
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyboardProfile {
+    pub class: String,
+    pub policy: KeyboardPolicy,
+}
+
+// When and where automatic saves happen
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveRules {
+    pub trigger: SaveTrigger,
+    // Buttons whose clicks save, as path::BUTTON_* bits
+    pub click_buttons: u8,
+    // Skip saves over MouseMinder's own window, e.g. after clicking Start
+    pub skip_own_window: bool,
+    pub zones: Vec<ExclusionZone>,
+    pub window_rules: Vec<WindowRule>,
+    // Wait this long after a mouse button is released before an idle save,
+    // idle saves never happen while one is held
+    pub release_quiet_ms: u64,
+    // Keyboard policy of applications without a profile
+    pub keyboard_policy: KeyboardPolicy,
+    pub keyboard_profiles: Vec<KeyboardProfile>,
+}
+
+impl Default for SaveRules {
+    fn default() -> Self {
+        Self {
+            trigger: SaveTrigger::default(),
+            click_buttons: BUTTON_LEFT,
+            skip_own_window: true,
+            zones: Vec::new(),
+            window_rules: Vec::new(),
+            release_quiet_ms: 0,
+            keyboard_policy: KeyboardPolicy::default(),
+            keyboard_profiles: Vec::new(),
+        }
+    }
+}
+
+impl SaveRules {
+    // The keyboard policy while an application is focused
+    pub fn keyboard_policy_for(&self, app: Option<&str>) -> KeyboardPolicy {
+        self.keyboard_profiles
+            .iter()
+            .find(|profile| Some(profile.class.as_str()) == app)
+            .map_or(self.keyboard_policy, |profile| profile.policy)
+    }
+}
+
+// Why a candidate idle save was skipped
+#[derive(Clone, Debug)]
+pub enum SkipReason {
+    OwnWindow,
+    Zone(String),
+    Window(String),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OwnWindow => write!(f, "Over the MouseMinder window"),
+            Self::Zone(name) => write!(f, "Inside the exclusion zone {}", name),
+            Self::Window(window) => write!(f, "Over {}, which matches a window rule", window),
+        }
+    }
+}
+
+// The rules along with where our own window is, as the tracking thread
+// checks candidate saves against them
+#[derive(Clone, Debug, Default)]
+pub struct SaveFilter {
+    pub rules: SaveRules,
+    // MouseMinder's viewport in physical pixels, None while hidden
+    pub own_window: Option<ExclusionZone>,
+    // Keyboard policy of the focused application
+    pub keyboard_policy: KeyboardPolicy,
+}
+
+impl SaveFilter {
+    pub fn check(&self, position: &SavedPosition) -> Option<SkipReason> {
+        let (x, y) = position.physical();
+
+        if self.rules.skip_own_window
+            && self
+                .own_window
+                .as_ref()
+                .is_some_and(|window| window.contains(x, y))
+        {
+            return Some(SkipReason::OwnWindow);
+        }
+
+        if let Some(zone) = self.rules.zones.iter().find(|zone| zone.contains(x, y)) {
+            return Some(SkipReason::Zone(zone.name.clone()));
+        }
+
+        let window = &position.window.as_ref()?.window;
+        self.rules
+            .window_rules
+            .iter()
+            .any(|rule| rule.matches(window))
+            .then(|| SkipReason::Window(window.label()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(x: i32, y: i32, width: i32, height: i32) -> ExclusionZone {
+        ExclusionZone {
+            name: "Zone".into(),
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn zones_contain_their_area() {
+        let taskbar = zone(0, 1040, 1920, 40);
+        assert!(taskbar.contains(0, 1040));
+        assert!(taskbar.contains(1919, 1079));
+        assert!(!taskbar.contains(1920, 1079));
+        assert!(!taskbar.contains(0, 1039));
+        assert!(!zone(10, 10, 0, 0).contains(10, 10));
+    }
+
+    #[test]
+    fn large_zones_do_not_overflow() {
+        let huge = zone(i32::MAX - 10, i32::MAX - 10, i32::MAX, i32::MAX);
+        assert!(huge.contains(i32::MAX, i32::MAX));
+        assert!(!huge.contains(0, 0));
+
+        let everywhere = zone(i32::MIN, i32::MIN, i32::MAX, i32::MAX);
+        assert!(everywhere.contains(-5, -5));
+        assert!(!everywhere.contains(5, 5));
+    }
+}
@@ -0,0 +1,186 @@
+use serde::Serialize;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::export::{csv_field, rfc3339};
+use crate::tracker::{TimedEvent, TrackerEvent};
+
+// A tracker event and when it happened
+pub struct SessionEntry {
+    pub offset: Duration,
+    pub timestamp: SystemTime,
+    pub event: TrackerEvent,
+}
+
+// Every tracker event since the session started, which is app launch or the
+// start of the latest recording
+pub struct SessionLog {
+    started: Instant,
+    started_at: SystemTime,
+    // Started along with a recording rather than at launch
+    recorded: bool,
+    entries: Vec<SessionEntry>,
+}
+
+// One exported row
+#[derive(Serialize)]
+struct Record {
+    offset_seconds: f64,
+    timestamp: String,
+    event: &'static str,
+    x: Option<i32>,
+    y: Option<i32>,
+    // Slot restored to, empty for the saved position
+    slot: Option<String>,
+    detail: Option<String>,
+}
+
+impl SessionLog {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            started_at: SystemTime::now(),
+            recorded: false,
+            entries: Vec::new(),
+        }
+    }
+
+    // A session whose offsets line up with a recording started now
+    pub fn for_recording() -> Self {
+        Self {
+            recorded: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn record(&mut self, event: TimedEvent) {
+        // Still queued from before the session started
+        let Some(offset) = event.at.checked_duration_since(self.started) else {
+            return;
+        };
+        self.entries.push(SessionEntry {
+            offset,
+            timestamp: event.timestamp,
+            event: event.event,
+        });
+    }
+
+    pub fn entries(&self) -> &[SessionEntry] {
+        &self.entries
+    }
+
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+
+    pub fn is_recorded(&self) -> bool {
+        self.recorded
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("offset_seconds,timestamp,event,x,y,slot,detail\n");
+        for record in self.records() {
+            csv.push_str(&format!(
+                "{:.6},{},{},{},{},{},{}\n",
+                record.offset_seconds,
+                record.timestamp,
+                record.event,
+                optional(record.x),
+                optional(record.y),
+                csv_field(record.slot.as_deref().unwrap_or_default()),
+                csv_field(record.detail.as_deref().unwrap_or_default()),
+            ));
+        }
+        csv
+    }
+
+    pub fn to_json_lines(&self) -> String {
+        self.records()
+            .map(|record| serde_json::to_string(&record).unwrap() + "\n")
+            .collect()
+    }
+
+    fn records(&self) -> impl Iterator<Item = Record> + '_ {
+        self.entries.iter().map(|entry| {
+            let (coords, detail) = match &entry.event {
+                TrackerEvent::PositionSaved(pos)
+                | TrackerEvent::PositionRestored { position: pos, .. } => {
+                    (Some((pos.x, pos.y)), None)
+                }
+                TrackerEvent::RestoreFailed(err) => (None, Some(err.to_string())),
+                TrackerEvent::SaveSkipped(reason) => (None, Some(reason.to_string())),
+                TrackerEvent::RestoreAdjusted(adjustment) => (
+                    Some(adjustment.to),
+                    Some(format!(
+                        "{} from X: {}, Y: {}",
+                        adjustment.policy.label(),
+                        adjustment.from.0,
+                        adjustment.from.1
+                    )),
+                ),
+                _ => (None, None),
+            };
+            Record {
+                offset_seconds: entry.offset.as_secs_f64(),
+                timestamp: rfc3339(entry.timestamp),
+                event: entry.event.name(),
+                x: coords.map(|(x, _)| x),
+                y: coords.map(|(_, y)| y),
+                slot: match &entry.event {
+                    TrackerEvent::PositionRestored { slot, .. } => slot.clone(),
+                    _ => None,
+                },
+                detail,
+            }
+        })
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::SavedPosition;
+
+    // A recording with a restore to the saved position and one to a slot
+    fn session() -> SessionLog {
+        let mut session = SessionLog::for_recording();
+        for slot in [None, Some("timeline, left".to_string())] {
+            session.record(TimedEvent::now(TrackerEvent::PositionRestored {
+                position: SavedPosition::at(100, 200, &[]),
+                slot,
+            }));
+        }
+        session
+    }
+
+    #[test]
+    fn csv_has_the_slot_restored_to() {
+        let csv = session().to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "offset_seconds,timestamp,event,x,y,slot,detail");
+
+        let fields = |line: &str| line.split(',').skip(2).collect::<Vec<_>>().join(",");
+        assert_eq!(fields(lines[1]), "position_restored,100,200,,");
+        assert_eq!(
+            fields(lines[2]),
+            "position_restored,100,200,\"timeline, left\","
+        );
+    }
+
+    #[test]
+    fn json_lines_have_the_slot_restored_to() {
+        let json = session().to_json_lines();
+        let records: Vec<serde_json::Value> = json
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["event"], "position_restored");
+        assert!(records[0]["slot"].is_null());
+        assert_eq!(records[1]["slot"], "timeline, left");
+        assert_eq!(records[1]["x"], 100);
+    }
+}
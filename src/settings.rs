@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use crate::export;
+use crate::markers::MarkerSettings;
+use crate::notifications::NotificationSettings;
+use crate::overlay::{OverlaySettings, PulseSettings};
+use crate::replay::ReplaySettings;
+use crate::rules::SaveRules;
+use crate::tour::TourSettings;
+use crate::tracker::RestoreSettings;
+
+// Key the settings are stored under in the eframe storage
+pub const STORAGE_KEY: &str = "settings";
+
+// User settings persisted between runs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Tray
+    pub close_to_tray: bool,
+    pub start_minimized: bool,
+
+    // Startup
+    pub autostart_start_tracking: bool,
+
+    // Toggling tracking off pauses it for this many minutes, 0 stops it
+    pub pause_minutes: u32,
+
+    pub overlay: OverlaySettings,
+    pub pulse: PulseSettings,
+    pub notifications: NotificationSettings,
+    pub restore: RestoreSettings,
+    pub save_rules: SaveRules,
+    pub tour: TourSettings,
+
+    // Exports
+    pub export_dir: String,
+    pub markers: MarkerSettings,
+    // Record the full cursor path while a recording runs
+    pub record_path: bool,
+    pub replay: ReplaySettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            close_to_tray: false,
+            start_minimized: false,
+            autostart_start_tracking: true,
+            pause_minutes: 0,
+            overlay: OverlaySettings::default(),
+            pulse: PulseSettings::default(),
+            notifications: NotificationSettings::default(),
+            restore: RestoreSettings::default(),
+            save_rules: SaveRules::default(),
+            tour: TourSettings::default(),
+            export_dir: export::default_export_dir(),
+            markers: MarkerSettings::default(),
+            record_path: false,
+            replay: ReplaySettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|storage| eframe::get_value(storage, STORAGE_KEY))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, STORAGE_KEY, self);
+    }
+}
@@ -0,0 +1,518 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::monitors::{self, CoordinateSpace, Monitor, MonitorPosition};
+use crate::tracker::SavedPosition;
+
+// Identifies exported slot sets
+const SLOT_SET_FORMAT: &str = "mouse_minder.slots";
+const SLOT_SET_VERSION: u32 = 1;
+
+// A position kept under a name, e.g. "timeline" or "export button"
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Slot {
+    pub name: String,
+    pub position: SavedPosition,
+    // Window class of the application the slot belongs to, None for all
+    #[serde(default)]
+    pub app: Option<String>,
+}
+
+impl Slot {
+    pub fn is_in_set(&self, app: Option<&str>) -> bool {
+        self.app.is_none() || self.app.as_deref() == app
+    }
+}
+
+// One stop of a tour: the slot to visit and what to do there
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TourStop {
+    pub slot: String,
+    pub dwell_secs: f32,
+    pub click: bool,
+}
+
+// Named slots and the order a tour visits them in
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TourSettings {
+    pub slots: Vec<Slot>,
+    pub stops: Vec<TourStop>,
+}
+
+impl TourSettings {
+    pub fn slot(&self, name: &str) -> Option<&Slot> {
+        self.slots.iter().find(|slot| slot.name == name)
+    }
+
+    // Slots of an application's set, those for all applications included
+    pub fn slots_for<'a>(&'a self, app: Option<&'a str>) -> impl Iterator<Item = &'a Slot> + 'a {
+        self.slots.iter().filter(move |slot| slot.is_in_set(app))
+    }
+
+    // A slot of an application's set by name, the application's own slot
+    // winning over one for all applications
+    pub fn slot_in_set(&self, name: &str, app: Option<&str>) -> Option<&Slot> {
+        self.slots
+            .iter()
+            .filter(|slot| slot.name == name && slot.is_in_set(app))
+            .max_by_key(|slot| slot.app.is_some())
+    }
+
+    // A name not used by any slot yet
+    pub fn next_slot_name(&self) -> String {
+        (1..)
+            .map(|number| format!("Slot {}", number))
+            .find(|name| self.slot(name).is_none())
+            .unwrap()
+    }
+}
+
+// Slots in resolution independent form, to carry them to another setup
+#[derive(Serialize, Deserialize)]
+struct SlotSet {
+    format: String,
+    version: u32,
+    slots: Vec<NormalizedSlot>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NormalizedSlot {
+    name: String,
+    monitor: String,
+    edid: Option<String>,
+    #[serde(default)]
+    app: Option<String>,
+    // Fractions of the monitor's width and height
+    x: f64,
+    y: f64,
+}
+
+impl TourSettings {
+    // Slots as monitor fractions in JSON, with the number of slots written.
+    // Slots that are on no monitor are left out.
+    pub fn export_slots(&self, monitors: &[Monitor]) -> (String, usize) {
+        let slots: Vec<NormalizedSlot> = self
+            .slots
+            .iter()
+            .filter_map(|slot| {
+                let position = slot.position.monitor.clone().or_else(|| {
+                    let (x, y) = slot.position.physical();
+                    MonitorPosition::locate(monitors, x, y)
+                })?;
+                let (x, y) = position.fraction();
+                Some(NormalizedSlot {
+                    name: slot.name.clone(),
+                    monitor: position.monitor.name,
+                    edid: position.monitor.edid,
+                    app: slot.app.clone(),
+                    x,
+                    y,
+                })
+            })
+            .collect();
+        let count = slots.len();
+
+        let set = SlotSet {
+            format: SLOT_SET_FORMAT.into(),
+            version: SLOT_SET_VERSION,
+            slots,
+        };
+        (serde_json::to_string_pretty(&set).unwrap(), count)
+    }
+
+    // Add exported slots, placed on the matching monitor of this setup.
+    // Slots with a name already in use are replaced.
+    pub fn import_slots(&mut self, json: &str, monitors: &[Monitor]) -> Result<usize, String> {
+        let set: SlotSet = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        if set.format != SLOT_SET_FORMAT || set.version != SLOT_SET_VERSION {
+            return Err("Not a MouseMinder slot set".into());
+        }
+
+        for imported in &set.slots {
+            let monitor =
+                monitors::find_match(monitors, &imported.monitor, imported.edid.as_deref())
+                    .ok_or("No monitors to place the slots on")?;
+            let (x, y) = monitor.at_fraction(imported.x, imported.y);
+            let (x, y) =
+                CoordinateSpace::desktop().convert_from_physical(x, y, monitor.scale_factor);
+            let slot = Slot {
+                name: imported.name.clone(),
+                position: SavedPosition::at(x, y, monitors),
+                app: imported.app.clone(),
+            };
+
+            match self
+                .slots
+                .iter_mut()
+                .find(|existing| existing.name == slot.name)
+            {
+                Some(existing) => *existing = slot,
+                None => self.slots.push(slot),
+            }
+        }
+        Ok(set.slots.len())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TourState {
+    Idle,
+    Playing { stop: usize, until: Instant },
+    Paused { stop: usize, remaining: Duration },
+}
+
+// Steps through the tour stops. Methods return the index of a stop the cursor
+// should move to now, moving it is left to the caller.
+pub struct TourPlayer {
+    state: TourState,
+}
+
+impl TourPlayer {
+    pub fn new() -> Self {
+        Self {
+            state: TourState::Idle,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state != TourState::Idle
+    }
+
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, TourState::Paused { .. })
+    }
+
+    // Start the tour, or pause and resume a running one
+    pub fn play_pause(&mut self, tour: &TourSettings) -> Option<usize> {
+        let now = Instant::now();
+        match self.state {
+            TourState::Idle => self.go_to(tour, 0, false),
+            TourState::Playing { stop, until } => {
+                self.state = TourState::Paused {
+                    stop,
+                    remaining: until.saturating_duration_since(now),
+                };
+                None
+            }
+            TourState::Paused { stop, remaining } => {
+                self.state = TourState::Playing {
+                    stop,
+                    until: now + remaining,
+                };
+                None
+            }
+        }
+    }
+
+    // Move on to the next stop right away. Starting a tour this way keeps it
+    // paused so every stop can be stepped through by hand.
+    pub fn advance(&mut self, tour: &TourSettings) -> Option<usize> {
+        match self.state {
+            TourState::Idle => self.go_to(tour, 0, true),
+            TourState::Playing { stop, .. } => self.go_to(tour, stop + 1, false),
+            TourState::Paused { stop, .. } => self.go_to(tour, stop + 1, true),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.state = TourState::Idle;
+    }
+
+    // Move on once the dwell time at the current stop is over
+    pub fn tick(&mut self, tour: &TourSettings) -> Option<usize> {
+        match self.state {
+            TourState::Playing { stop, until } if Instant::now() >= until => {
+                self.go_to(tour, stop + 1, false)
+            }
+            _ => None,
+        }
+    }
+
+    // Time until the next automatic step, to schedule a repaint
+    pub fn time_to_next_stop(&self) -> Option<Duration> {
+        match self.state {
+            TourState::Playing { until, .. } => {
+                Some(until.saturating_duration_since(Instant::now()))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn status(&self, tour: &TourSettings) -> Option<String> {
+        let (stop, paused) = match self.state {
+            TourState::Idle => return None,
+            TourState::Playing { stop, .. } => (stop, false),
+            TourState::Paused { stop, .. } => (stop, true),
+        };
+        let name = tour.stops.get(stop).map_or("", |stop| stop.slot.as_str());
+        Some(format!(
+            "Tour stop {}/{}: {}{}",
+            stop + 1,
+            tour.stops.len(),
+            name,
+            if paused { " (paused)" } else { "" }
+        ))
+    }
+
+    // Go to a stop, ending the tour after the last one
+    fn go_to(&mut self, tour: &TourSettings, stop: usize, paused: bool) -> Option<usize> {
+        let Some(tour_stop) = tour.stops.get(stop) else {
+            self.state = TourState::Idle;
+            return None;
+        };
+
+        // Settings files may hold any number, including inf and NaN
+        let dwell = Duration::try_from_secs_f32(tour_stop.dwell_secs).unwrap_or_default();
+        self.state = if paused {
+            TourState::Paused {
+                stop,
+                remaining: dwell,
+            }
+        } else {
+            TourState::Playing {
+                stop,
+                until: Instant::now() + dwell,
+            }
+        };
+        Some(stop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(name: &str, app: Option<&str>, x: i32) -> Slot {
+        Slot {
+            name: name.into(),
+            position: SavedPosition::at(x, 0, &[]),
+            app: app.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn slot_sets_follow_the_application() {
+        let tour = TourSettings {
+            slots: vec![
+                slot("timeline", None, 1),
+                slot("export", Some("Kdenlive"), 2),
+                slot("timeline", Some("Kdenlive"), 3),
+                slot("export", Some("Blender"), 4),
+            ],
+            stops: Vec::new(),
+        };
+
+        let set = |app| {
+            tour.slots_for(app)
+                .map(|slot| slot.position.x)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(set(None), [1]);
+        assert_eq!(set(Some("Kdenlive")), [1, 2, 3]);
+        assert_eq!(set(Some("Blender")), [1, 4]);
+
+        let found = |name, app| tour.slot_in_set(name, app).map(|slot| slot.position.x);
+        assert_eq!(found("timeline", None), Some(1));
+        // The application's own slot wins over the one for all applications
+        assert_eq!(found("timeline", Some("Kdenlive")), Some(3));
+        assert_eq!(found("timeline", Some("Blender")), Some(1));
+        assert_eq!(found("export", Some("Blender")), Some(4));
+        assert_eq!(found("export", None), None);
+    }
+
+    fn monitor(name: &str, edid: &str, x: i32, y: i32, width: u32, height: u32) -> Monitor {
+        Monitor {
+            name: name.into(),
+            edid: Some(edid.into()),
+            x,
+            y,
+            width,
+            height,
+            scale_factor: 1.0,
+            primary: false,
+        }
+    }
+
+    #[test]
+    fn slots_move_with_their_monitor_between_layouts() {
+        // A laptop with an external monitor to its right and one to its left
+        let old_layout = [
+            monitor("eDP-1", "AAA-1", 0, 0, 1920, 1080),
+            monitor("HDMI-1", "BBB-2", 1920, 0, 2560, 1440),
+            monitor("DP-3", "CCC-3", -1280, 0, 1280, 1024),
+        ];
+        let old_tour = TourSettings {
+            slots: [
+                ("laptop", 960, 540),
+                ("external", 2560, 360),
+                ("side", -640, 512),
+            ]
+            .into_iter()
+            .map(|(name, x, y)| Slot {
+                name: name.into(),
+                position: SavedPosition::at(x, y, &old_layout),
+                app: None,
+            })
+            .collect(),
+            stops: Vec::new(),
+        };
+        let (json, count) = old_tour.export_slots(&old_layout);
+        assert_eq!(count, 3);
+
+        // The external monitor is now 4K on another connector, left of the
+        // laptop, and the side monitor is gone
+        let mut laptop = monitor("eDP-1", "AAA-1", 3840, 0, 1920, 1080);
+        laptop.primary = true;
+        let new_layout = [monitor("DP-1", "BBB-2", 0, 0, 3840, 2160), laptop];
+        let mut new_tour = TourSettings {
+            slots: vec![slot("laptop", None, 0)],
+            stops: Vec::new(),
+        };
+        assert_eq!(new_tour.import_slots(&json, &new_layout), Ok(3));
+
+        let placed = |name| {
+            let slot = new_tour
+                .slots
+                .iter()
+                .find(|slot| slot.name == name)
+                .unwrap();
+            let monitor = slot.position.monitor.as_ref().unwrap();
+            (
+                (slot.position.x, slot.position.y),
+                monitor.monitor.name.as_str(),
+            )
+        };
+        // Existing slots with the same name are replaced
+        assert_eq!(new_tour.slots.len(), 3);
+        assert_eq!(placed("laptop"), ((4800, 540), "eDP-1"));
+        assert_eq!(placed("external"), ((960, 540), "DP-1"));
+        // A slot on a missing monitor lands on the primary one
+        assert_eq!(placed("side"), ((4800, 540), "eDP-1"));
+    }
+
+    #[test]
+    fn import_refuses_other_files() {
+        let mut tour = TourSettings::default();
+        assert!(tour.import_slots("{}", &[]).is_err());
+        assert!(
+            tour.import_slots(r#"{"format":"other","version":1,"slots":[]}"#, &[])
+                .is_err()
+        );
+    }
+
+    fn tour(dwell_secs: f32) -> TourSettings {
+        TourSettings {
+            slots: Vec::new(),
+            stops: ["a", "b", "c"]
+                .into_iter()
+                .map(|slot| TourStop {
+                    slot: slot.into(),
+                    dwell_secs,
+                    click: false,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn play_starts_at_the_first_stop() {
+        let tour = tour(60.0);
+        let mut player = TourPlayer::new();
+        assert!(!player.is_running());
+        assert_eq!(player.status(&tour), None);
+
+        assert_eq!(player.play_pause(&tour), Some(0));
+        assert!(player.is_running());
+        assert!(!player.is_paused());
+        assert!(player.time_to_next_stop().unwrap() > Duration::from_secs(59));
+        assert_eq!(player.status(&tour).unwrap(), "Tour stop 1/3: a");
+    }
+
+    #[test]
+    fn pause_keeps_the_remaining_dwell() {
+        let tour = tour(60.0);
+        let mut player = TourPlayer::new();
+        player.play_pause(&tour);
+
+        assert_eq!(player.play_pause(&tour), None);
+        assert!(player.is_paused());
+        assert_eq!(player.time_to_next_stop(), None);
+        assert_eq!(player.tick(&tour), None);
+        assert_eq!(player.status(&tour).unwrap(), "Tour stop 1/3: a (paused)");
+
+        // Resuming stays at the stop with what was left of its dwell
+        assert_eq!(player.play_pause(&tour), None);
+        assert!(!player.is_paused());
+        let remaining = player.time_to_next_stop().unwrap();
+        assert!(remaining > Duration::from_secs(59) && remaining <= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn next_steps_through_the_stops() {
+        let tour = tour(60.0);
+        let mut player = TourPlayer::new();
+
+        // Starting with next keeps the tour paused at every stop
+        assert_eq!(player.advance(&tour), Some(0));
+        assert!(player.is_paused());
+        assert_eq!(player.advance(&tour), Some(1));
+        assert!(player.is_paused());
+
+        // While playing, next keeps playing
+        player.play_pause(&tour);
+        assert_eq!(player.advance(&tour), Some(2));
+        assert!(!player.is_paused());
+
+        // Past the last stop the tour ends
+        assert_eq!(player.advance(&tour), None);
+        assert!(!player.is_running());
+    }
+
+    #[test]
+    fn tick_moves_on_after_the_dwell() {
+        let mut player = TourPlayer::new();
+        let waiting = tour(60.0);
+        player.play_pause(&waiting);
+        assert_eq!(player.tick(&waiting), None);
+
+        let tour = tour(0.0);
+        let mut player = TourPlayer::new();
+        assert_eq!(player.play_pause(&tour), Some(0));
+        assert_eq!(player.tick(&tour), Some(1));
+        assert_eq!(player.tick(&tour), Some(2));
+        assert_eq!(player.tick(&tour), None);
+        assert!(!player.is_running());
+    }
+
+    #[test]
+    fn stop_ends_the_tour() {
+        let tour = tour(60.0);
+        let mut player = TourPlayer::new();
+        player.play_pause(&tour);
+        player.stop();
+        assert!(!player.is_running());
+        assert_eq!(player.tick(&tour), None);
+        // Playing again starts over
+        assert_eq!(player.play_pause(&tour), Some(0));
+    }
+
+    #[test]
+    fn empty_tours_do_not_start() {
+        let tour = TourSettings::default();
+        let mut player = TourPlayer::new();
+        assert_eq!(player.play_pause(&tour), None);
+        assert_eq!(player.advance(&tour), None);
+        assert!(!player.is_running());
+    }
+
+    #[test]
+    fn non_finite_dwell_times_do_not_panic() {
+        for dwell_secs in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY, -1.0] {
+            let tour = tour(dwell_secs);
+            let mut player = TourPlayer::new();
+            assert_eq!(player.play_pause(&tour), Some(0));
+            assert_eq!(player.tick(&tour), Some(1));
+        }
+    }
+}
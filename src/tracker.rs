@@ -1,21 +1,343 @@
 use device_query::{DeviceQuery, DeviceState};
-use enigo::{Enigo, Mouse, Settings};
+use enigo::{Button, Direction, Enigo, Mouse, Settings};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant, SystemTime};
 
+use crate::monitors::{self, CoordinateSpace, Monitor, MonitorPosition};
+use crate::path::{self, CursorPath, PathRecorder};
+use crate::rules::{KeyboardPolicy, SaveFilter, SkipReason};
+use crate::tour::Slot;
+use crate::windows::{self, WindowAnchor};
+
 // Structure to hold saved position information
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SavedPosition {
     pub x: i32,
     pub y: i32,
     pub timestamp: SystemTime,
+    // The monitor the position was saved on, to survive layout changes
+    #[serde(default)]
+    pub monitor: Option<MonitorPosition>,
+    // What x and y are measured in, and the monitor's scale to convert them
+    #[serde(default)]
+    pub space: CoordinateSpace,
+    #[serde(default = "default_scale_factor")]
+    pub scale_factor: f32,
+    // Mouse buttons held when the position was saved, as path::BUTTON_* bits
+    #[serde(default)]
+    pub buttons: u8,
+    // The window under the cursor when the position was saved
+    #[serde(default)]
+    pub window: Option<WindowAnchor>,
+}
+
+fn default_scale_factor() -> f32 {
+    1.0
+}
+
+// What to do with a restore target outside every monitor
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BoundsPolicy {
+    // Clamp to the bounding box of all monitors, or the nearest monitor if
+    // that lands in a gap between them
+    Clamp,
+    // Move to the closest point on the closest monitor
+    #[default]
+    NearestMonitor,
+    // Do not move the cursor at all
+    Refuse,
+}
+
+impl BoundsPolicy {
+    pub const ALL: [Self; 3] = [Self::Clamp, Self::NearestMonitor, Self::Refuse];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Clamp => "Clamp to the desktop",
+            Self::NearestMonitor => "Nearest monitor",
+            Self::Refuse => "Refuse",
+        }
+    }
+}
+
+// How saved positions are turned into restore targets
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestoreSettings {
+    // Treat positions as fractions of their monitor, so they carry over to
+    // other resolutions and to setups with different monitors
+    pub relative_positions: bool,
+    // Restore relative to the window the position was saved over, wherever
+    // that window is now
+    pub window_relative: bool,
+    // Raise and focus the saved window before moving the cursor into it
+    pub focus_window: bool,
+    // Keep the last position per application, restores use the focused
+    // application's one
+    pub per_app_positions: bool,
+    pub bounds_policy: BoundsPolicy,
+}
+
+// A restore target that was off screen and moved by the bounds policy
+#[derive(Clone, Debug)]
+pub struct RestoreAdjustment {
+    pub policy: BoundsPolicy,
+    pub from: (i32, i32),
+    pub to: (i32, i32),
+}
+
+impl SavedPosition {
+    // A position saved now, along with the monitor and the window it is on
+    // and the buttons held
+    pub fn capture(x: i32, y: i32, buttons: u8) -> Self {
+        let mut position = Self::at(x, y, &monitors::current());
+        position.buttons = buttons;
+        let (physical_x, physical_y) = position.physical();
+        position.window = WindowAnchor::capture(physical_x, physical_y);
+        position
+    }
+
+    // A position saved now on the given monitor layout, with x and y as
+    // device_query reports them
+    pub fn at(x: i32, y: i32, monitors: &[Monitor]) -> Self {
+        let space = CoordinateSpace::desktop();
+        let monitor = monitors.iter().find(|monitor| {
+            let (physical_x, physical_y) = space.to_physical(x, y, monitor.scale_factor);
+            monitor.contains(physical_x, physical_y)
+        });
+        let scale_factor = monitor.map_or(1.0, |monitor| monitor.scale_factor);
+        let (physical_x, physical_y) = space.to_physical(x, y, scale_factor);
+
+        Self {
+            x,
+            y,
+            timestamp: SystemTime::now(),
+            monitor: MonitorPosition::locate(monitors, physical_x, physical_y),
+            space,
+            scale_factor,
+            buttons: 0,
+            window: None,
+        }
+    }
+
+    pub fn physical(&self) -> (i32, i32) {
+        self.space.to_physical(self.x, self.y, self.scale_factor)
+    }
+
+    pub fn logical(&self) -> (i32, i32) {
+        let (x, y) = self.physical();
+        CoordinateSpace::Logical.convert_from_physical(x, y, self.scale_factor)
+    }
+
+    // Coordinates for display, as monitor percentages when positions are
+    // relative
+    pub fn describe(&self, settings: &RestoreSettings) -> String {
+        match &self.monitor {
+            Some(monitor) if settings.relative_positions => {
+                let (fraction_x, fraction_y) = monitor.fraction();
+                format!(
+                    "X: {:.1}%, Y: {:.1}% of {}",
+                    fraction_x * 100.0,
+                    fraction_y * 100.0,
+                    monitor.monitor.name
+                )
+            }
+            _ => format!("X: {}, Y: {}", self.x, self.y),
+        }
+    }
+
+    // Whether the saved monitor is gone. Positions without monitor details
+    // and layouts that could not be queried are taken as they are.
+    pub fn is_on_missing_monitor(&self, monitors: &[Monitor]) -> bool {
+        !monitors.is_empty()
+            && self
+                .monitor
+                .as_ref()
+                .is_some_and(|monitor| !monitor.is_connected(monitors))
+    }
+
+    // Where to move the cursor with the monitors connected now, in physical
+    // pixels: the same spot in the saved window if restoring relative to
+    // windows, else the same spot on the saved monitor, or the nearest point
+    // on any monitor if it is gone. Targets outside every monitor are handled
+    // by the bounds policy, which is reported back.
+    pub fn target(
+        &self,
+        monitors: &[Monitor],
+        settings: &RestoreSettings,
+    ) -> Result<((i32, i32), Option<RestoreAdjustment>), RestoreError> {
+        if settings.window_relative
+            && let Some((x, y)) = self.window.as_ref().and_then(WindowAnchor::remap)
+        {
+            return keep_on_screen(x, y, monitors, settings.bounds_policy);
+        }
+
+        let (x, y) = self.physical();
+
+        // Without a known layout there is nothing to check against
+        if monitors.is_empty() {
+            return Ok(((x, y), None));
+        }
+
+        let remapped = self.monitor.as_ref().and_then(|monitor| {
+            if settings.relative_positions {
+                monitor.remap_relative(monitors)
+            } else {
+                monitor.remap(monitors)
+            }
+        });
+        if let Some(target) = remapped {
+            return Ok((target, None));
+        }
+
+        // device_query reports 0, 0 when it could not reach the display
+        if self.monitor.is_none() && (x, y) == (0, 0) {
+            return Err(RestoreError::NoDisplay);
+        }
+        keep_on_screen(x, y, monitors, settings.bounds_policy)
+    }
+}
+
+// A target as it is if it is on a monitor, else moved there by the policy
+fn keep_on_screen(
+    x: i32,
+    y: i32,
+    monitors: &[Monitor],
+    policy: BoundsPolicy,
+) -> Result<((i32, i32), Option<RestoreAdjustment>), RestoreError> {
+    if monitors.is_empty() || monitors::at(monitors, x, y).is_some() {
+        return Ok(((x, y), None));
+    }
+
+    let target = match policy {
+        BoundsPolicy::Clamp => monitors::clamp_to_desktop(monitors, x, y),
+        BoundsPolicy::NearestMonitor => monitors::clamp_to_nearest(monitors, x, y),
+        BoundsPolicy::Refuse => None,
+    }
+    .ok_or(RestoreError::OutOfBounds { x, y })?;
+
+    Ok((
+        target,
+        Some(RestoreAdjustment {
+            policy,
+            from: (x, y),
+            to: target,
+        }),
+    ))
+}
+
+// Make a position the saved one, and the saved one of the application it
+// is over
+fn store_position(
+    position: SavedPosition,
+    saved_position: &Mutex<Option<SavedPosition>>,
+    app_positions: &Mutex<HashMap<String, SavedPosition>>,
+    event_sender: &Sender<TimedEvent>,
+) {
+    if let Some(anchor) = &position.window {
+        app_positions
+            .lock()
+            .unwrap()
+            .insert(anchor.window.class.clone(), position.clone());
+    }
+    *saved_position.lock().unwrap() = Some(position.clone());
+    let _ = event_sender.send(TimedEvent::now(TrackerEvent::PositionSaved(position)));
+}
+
+// Why restoring the cursor did not happen
+#[derive(Clone, Debug)]
+pub enum RestoreError {
+    NoSavedPosition,
+    // Nothing saved over windows of this class yet
+    NoSavedPositionIn(String),
+    // Saved while no display was available
+    NoDisplay,
+    OutOfBounds { x: i32, y: i32 },
+    Input(String),
+}
+
+impl fmt::Display for RestoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSavedPosition => write!(f, "No position saved yet"),
+            Self::NoSavedPositionIn(app) => write!(f, "No position saved in {} yet", app),
+            Self::NoDisplay => write!(f, "The position was saved without a display"),
+            Self::OutOfBounds { x, y } => write!(f, "X: {}, Y: {} is outside every monitor", x, y),
+            Self::Input(err) => write!(f, "Could not move the cursor: {}", err),
+        }
+    }
+}
+
+// Things the tracker did, in the order they happened
+#[derive(Clone, Debug)]
+pub enum TrackerEvent {
+    TrackingStarted,
+    TrackingStopped,
+    PositionSaved(SavedPosition),
+    // An idle save the save rules did not allow, once per resting spot
+    SaveSkipped(SkipReason),
+    // Where the cursor went, and the name of the slot when restoring to one
+    // rather than to the saved position
+    PositionRestored {
+        position: SavedPosition,
+        slot: Option<String>,
+    },
+    RestoreFailed(RestoreError),
+    // Follows PositionRestored when the target had to be moved on screen
+    RestoreAdjusted(RestoreAdjustment),
+    PositionReset,
+}
+
+impl TrackerEvent {
+    // Stable name used in exports
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TrackingStarted => "tracking_started",
+            Self::TrackingStopped => "tracking_stopped",
+            Self::PositionSaved(_) => "position_saved",
+            Self::SaveSkipped(_) => "save_skipped",
+            Self::PositionRestored { .. } => "position_restored",
+            Self::RestoreFailed(_) => "restore_failed",
+            Self::RestoreAdjusted(_) => "restore_adjusted",
+            Self::PositionReset => "position_reset",
+        }
+    }
+}
+
+// A tracker event along with when it was emitted, so its time does not
+// depend on when the UI gets to it
+#[derive(Clone, Debug)]
+pub struct TimedEvent {
+    pub at: Instant,
+    pub timestamp: SystemTime,
+    pub event: TrackerEvent,
+}
+
+impl TimedEvent {
+    pub fn now(event: TrackerEvent) -> Self {
+        Self {
+            at: Instant::now(),
+            timestamp: SystemTime::now(),
+            event,
+        }
+    }
 }
 
 // Core tracker functionality
 pub struct MouseTracker {
     is_tracking: Arc<Mutex<bool>>,
     saved_position: Arc<Mutex<Option<SavedPosition>>>,
+    // Last position saved over each application, by window class
+    app_positions: Arc<Mutex<HashMap<String, SavedPosition>>>,
+    save_filter: Arc<Mutex<SaveFilter>>,
+    path_recorder: Arc<Mutex<Option<PathRecorder>>>,
+    event_sender: Sender<TimedEvent>,
+    event_receiver: Receiver<TimedEvent>,
     _tracking_thread: Option<JoinHandle<()>>, // Store thread handle but don't expose it
 }
 
@@ -23,13 +345,28 @@ impl MouseTracker {
     pub fn new() -> Self {
         let is_tracking = Arc::new(Mutex::new(false));
         let saved_position = Arc::new(Mutex::new(None));
+        let app_positions = Arc::new(Mutex::new(HashMap::new()));
+        let save_filter = Arc::new(Mutex::new(SaveFilter::default()));
+        let path_recorder = Arc::new(Mutex::new(None));
+        let (event_sender, event_receiver) = mpsc::channel();
 
-        let tracking_thread =
-            Self::spawn_tracking_thread(Arc::clone(&is_tracking), Arc::clone(&saved_position));
+        let tracking_thread = Self::spawn_tracking_thread(
+            Arc::clone(&is_tracking),
+            Arc::clone(&saved_position),
+            Arc::clone(&app_positions),
+            Arc::clone(&save_filter),
+            Arc::clone(&path_recorder),
+            event_sender.clone(),
+        );
 
         Self {
             is_tracking,
             saved_position,
+            app_positions,
+            save_filter,
+            path_recorder,
+            event_sender,
+            event_receiver,
             _tracking_thread: Some(tracking_thread),
         }
     }
@@ -38,50 +375,138 @@ impl MouseTracker {
     fn spawn_tracking_thread(
         is_tracking: Arc<Mutex<bool>>,
         saved_position: Arc<Mutex<Option<SavedPosition>>>,
+        app_positions: Arc<Mutex<HashMap<String, SavedPosition>>>,
+        save_filter: Arc<Mutex<SaveFilter>>,
+        path_recorder: Arc<Mutex<Option<PathRecorder>>>,
+        event_sender: Sender<TimedEvent>,
     ) -> JoinHandle<()> {
         thread::spawn(move || {
             let device_state = DeviceState::new();
             let mut last_position = (0, 0);
             let mut last_movement_time = Instant::now();
+            // Resting spot the save rules already turned down
+            let mut skipped_position = None;
+            // Last time a mouse button was seen held
+            let mut last_button_time: Option<Instant> = None;
+            // Last time a key was seen held
+            let mut last_key_time: Option<Instant> = None;
+            // Buttons held at the last poll, to tell presses apart
+            let mut previous_buttons = 0;
 
             loop {
                 // Check if tracking is enabled
                 let tracking = { *is_tracking.lock().unwrap() };
 
+                // Record the full path independently of idle tracking
+                let recording_path = {
+                    let mut recorder = path_recorder.lock().unwrap();
+                    if let Some(recorder) = recorder.as_mut() {
+                        let mouse = device_state.get_mouse();
+                        recorder.sample(mouse.coords, &mouse.button_pressed);
+                    }
+                    recorder.is_some()
+                };
+
                 if tracking {
-                    // Get current mouse position
-                    let current_position = device_state.get_mouse().coords;
+                    // Get current mouse position and buttons
+                    let mouse = device_state.get_mouse();
+                    let current_position = mouse.coords;
+                    let buttons = path::button_mask(&mouse.button_pressed);
 
-                    // If position changed, update the last movement time
-                    if current_position.0 != last_position.0
-                        || current_position.1 != last_position.1
+                    // Holding a button still is a drag or a slider, not a rest.
+                    // Saves can wait for a quiet period after the release too.
+                    if buttons != 0 {
+                        last_button_time = Some(Instant::now());
+                    }
+                    let (quiet_period, keyboard_policy, trigger, click_buttons) = {
+                        let filter = save_filter.lock().unwrap();
+                        (
+                            Duration::from_millis(filter.rules.release_quiet_ms),
+                            filter.keyboard_policy,
+                            filter.rules.trigger,
+                            filter.rules.click_buttons,
+                        )
+                    };
+                    let buttons_quiet = buttons == 0
+                        && last_button_time.is_none_or(|time| time.elapsed() >= quiet_period);
+
+                    // Typing as a signal, the keyboard is only read when it matters
+                    if keyboard_policy != KeyboardPolicy::Ignore
+                        && !device_state.get_keys().is_empty()
                     {
+                        last_key_time = Some(Instant::now());
+                    }
+                    let inactivity_threshold =
+                        Duration::from_millis(crate::config::INACTIVITY_THRESHOLD_MS);
+                    let keyboard_quiet = match keyboard_policy {
+                        KeyboardPolicy::Ignore => true,
+                        KeyboardPolicy::SuppressWhileTyping => {
+                            last_key_time.is_none_or(|time| time < last_movement_time)
+                        }
+                        KeyboardPolicy::RequireIdle => {
+                            last_key_time.is_none_or(|time| time.elapsed() >= inactivity_threshold)
+                        }
+                    };
+
+                    // Presses since the last poll of the buttons that trigger click saves
+                    let clicked = buttons & !previous_buttons & click_buttons;
+                    previous_buttons = buttons;
+
+                    // If position changed, update the last movement time
+                    let moved = current_position != last_position;
+                    if moved {
                         last_movement_time = Instant::now();
                         last_position = current_position;
-                    } else {
-                        // Check if mouse has been still for the threshold time
-                        let elapsed = last_movement_time.elapsed().as_millis() as u64;
-                        if elapsed >= crate::config::INACTIVITY_THRESHOLD_MS {
-                            // Save the position if different from the last saved one
-                            let mut pos_guard = saved_position.lock().unwrap();
-                            let should_update = match pos_guard.as_ref() {
-                                None => true,
-                                Some(p) => p.x != current_position.0 || p.y != current_position.1,
-                            };
-
-                            if should_update {
-                                *pos_guard = Some(SavedPosition {
-                                    x: current_position.0,
-                                    y: current_position.1,
-                                    timestamp: SystemTime::now(),
-                                });
+                        skipped_position = None;
+                    }
+
+                    // Check if mouse has been still for the threshold time
+                    let idle_save = trigger.on_idle()
+                        && !moved
+                        && last_movement_time.elapsed() >= inactivity_threshold
+                        && buttons_quiet
+                        && keyboard_quiet;
+                    let click_save = trigger.on_click() && clicked != 0;
+
+                    if idle_save || click_save {
+                        // Save the position if different from the last saved one, so
+                        // a click where the cursor then rests is saved only once
+                        let is_saved = saved_position
+                            .lock()
+                            .unwrap()
+                            .as_ref()
+                            .is_some_and(|p| (p.x, p.y) == current_position);
+
+                        if !is_saved && skipped_position != Some(current_position) {
+                            let position = SavedPosition::capture(
+                                current_position.0,
+                                current_position.1,
+                                buttons,
+                            );
+                            let skip = save_filter.lock().unwrap().check(&position);
+                            if let Some(reason) = skip {
+                                skipped_position = Some(current_position);
+                                let _ = event_sender
+                                    .send(TimedEvent::now(TrackerEvent::SaveSkipped(reason)));
+                            } else {
+                                store_position(
+                                    position,
+                                    &saved_position,
+                                    &app_positions,
+                                    &event_sender,
+                                );
                             }
                         }
                     }
                 }
 
-                // Sleep to avoid high CPU usage
-                thread::sleep(Duration::from_millis(crate::config::POLL_INTERVAL_MS));
+                // Sleep to avoid high CPU usage, sample faster while recording a path
+                let interval = if recording_path {
+                    crate::config::PATH_SAMPLE_INTERVAL_MS
+                } else {
+                    crate::config::POLL_INTERVAL_MS
+                };
+                thread::sleep(Duration::from_millis(interval));
             }
         })
     }
@@ -89,13 +514,19 @@ impl MouseTracker {
     // Start tracking mouse movement
     pub fn start_tracking(&self) {
         let mut tracking = self.is_tracking.lock().unwrap();
-        *tracking = true;
+        if !*tracking {
+            *tracking = true;
+            self.emit(TrackerEvent::TrackingStarted);
+        }
     }
 
     // Stop tracking mouse movement
     pub fn stop_tracking(&self) {
         let mut tracking = self.is_tracking.lock().unwrap();
-        *tracking = false;
+        if *tracking {
+            *tracking = false;
+            self.emit(TrackerEvent::TrackingStopped);
+        }
     }
 
     // Check if currently tracking
@@ -103,27 +534,177 @@ impl MouseTracker {
         *self.is_tracking.lock().unwrap()
     }
 
+    // Start recording the full cursor path, unless already recording
+    pub fn start_path_recording(&self) {
+        let mut recorder = self.path_recorder.lock().unwrap();
+        if recorder.is_none() {
+            *recorder = Some(PathRecorder::new());
+        }
+    }
+
+    // Stop recording the cursor path, returning what was recorded
+    pub fn stop_path_recording(&self) -> Option<CursorPath> {
+        self.path_recorder
+            .lock()
+            .unwrap()
+            .take()
+            .map(PathRecorder::finish)
+    }
+
     // Get the last saved position
     pub fn get_saved_position(&self) -> Option<SavedPosition> {
         self.saved_position.lock().unwrap().clone()
     }
 
-    // Reset (clear) the saved position
+    // Save where the cursor is right now, whether tracking or not. Save rules
+    // only apply to automatic saves.
+    pub fn save_now(&self) {
+        let mouse = DeviceState::new().get_mouse();
+        let position = SavedPosition::capture(
+            mouse.coords.0,
+            mouse.coords.1,
+            path::button_mask(&mouse.button_pressed),
+        );
+        store_position(
+            position,
+            &self.saved_position,
+            &self.app_positions,
+            &self.event_sender,
+        );
+    }
+
+    // Rules for idle saves, along with where our own window is now
+    pub fn set_save_filter(&self, filter: SaveFilter) {
+        *self.save_filter.lock().unwrap() = filter;
+    }
+
+    // Get the last position saved over an application, or the last one
+    // overall without one
+    pub fn get_saved_position_for(&self, app: Option<&str>) -> Option<SavedPosition> {
+        match app {
+            Some(app) => self.app_positions.lock().unwrap().get(app).cloned(),
+            None => self.get_saved_position(),
+        }
+    }
+
+    // Reset (clear) the saved positions
     pub fn reset_position(&self) {
         let mut pos = self.saved_position.lock().unwrap();
         *pos = None;
+        self.app_positions.lock().unwrap().clear();
+        self.emit(TrackerEvent::PositionReset);
+    }
+
+    // Restore cursor to the position saved over an application, or the last
+    // one overall, returning the position moved to
+    pub fn restore_position(
+        &self,
+        app: Option<&str>,
+        settings: &RestoreSettings,
+    ) -> Result<SavedPosition, RestoreError> {
+        let result = self
+            .get_saved_position_for(app)
+            .ok_or_else(|| match app {
+                Some(app) => RestoreError::NoSavedPositionIn(app.into()),
+                None => RestoreError::NoSavedPosition,
+            })
+            .and_then(|pos| Self::move_to(pos, settings));
+        self.report_restore(result, None)
+    }
+
+    // Restore cursor to a tour slot
+    pub fn restore_to_slot(
+        &self,
+        slot: &Slot,
+        settings: &RestoreSettings,
+    ) -> Result<SavedPosition, RestoreError> {
+        let result = Self::move_to(slot.position.clone(), settings);
+        self.report_restore(result, Some(&slot.name))
+    }
+
+    // Left click wherever the cursor is
+    pub fn click(&self) -> Result<(), RestoreError> {
+        let mut enigo =
+            Enigo::new(&Settings::default()).map_err(|err| RestoreError::Input(err.to_string()))?;
+        enigo
+            .button(Button::Left, Direction::Click)
+            .map_err(|err| RestoreError::Input(err.to_string()))
     }
 
-    // Restore cursor to saved position
-    pub fn restore_position(&self) -> bool {
-        if let Some(pos) = self.get_saved_position() {
-            if let Ok(mut enigo) = Enigo::new(&Settings::default()) {
-                // Add the enigo::Coordinate enum to specify absolute positioning
-                let _ = enigo.move_mouse(pos.x, pos.y, enigo::Coordinate::Abs);
-                return true;
+    fn report_restore(
+        &self,
+        result: Result<(SavedPosition, Option<RestoreAdjustment>), RestoreError>,
+        slot: Option<&str>,
+    ) -> Result<SavedPosition, RestoreError> {
+        match result {
+            Ok((pos, adjustment)) => {
+                self.emit(TrackerEvent::PositionRestored {
+                    position: pos.clone(),
+                    slot: slot.map(str::to_owned),
+                });
+                if let Some(adjustment) = adjustment {
+                    self.emit(TrackerEvent::RestoreAdjusted(adjustment));
+                }
+                Ok(pos)
+            }
+            Err(err) => {
+                self.emit(TrackerEvent::RestoreFailed(err.clone()));
+                Err(err)
+            }
+        }
+    }
+
+    // Move to the position as remapped to the current monitors, returning
+    // where the cursor ended up in physical pixels
+    fn move_to(
+        pos: SavedPosition,
+        settings: &RestoreSettings,
+    ) -> Result<(SavedPosition, Option<RestoreAdjustment>), RestoreError> {
+        // Focus first, switching desktops can move the window on screen. The
+        // window manager does that after we asked, so wait for it.
+        if settings.focus_window
+            && let Some(anchor) = &pos.window
+        {
+            let timeout = Duration::from_millis(crate::config::FOCUS_WAIT_MS);
+            match windows::focus(&anchor.window) {
+                Ok(window) if !windows::wait_until_active(&window, timeout) => {
+                    eprintln!("{} did not become active in time", window.label());
+                }
+                Ok(_) => {}
+                Err(err) => eprintln!("Could not focus {}: {}", anchor.window.label(), err),
             }
         }
-        false
+
+        let monitors = monitors::current();
+        let ((x, y), adjustment) = pos.target(&monitors, settings)?;
+        let scale_factor =
+            monitors::at(&monitors, x, y).map_or(pos.scale_factor, |monitor| monitor.scale_factor);
+        let (output_x, output_y) =
+            CoordinateSpace::desktop().convert_from_physical(x, y, scale_factor);
+        let mut enigo =
+            Enigo::new(&Settings::default()).map_err(|err| RestoreError::Input(err.to_string()))?;
+
+        // Add the enigo::Coordinate enum to specify absolute positioning
+        enigo
+            .move_mouse(output_x, output_y, enigo::Coordinate::Abs)
+            .map_err(|err| RestoreError::Input(err.to_string()))?;
+        let pos = SavedPosition {
+            x,
+            y,
+            space: CoordinateSpace::Physical,
+            scale_factor,
+            ..pos
+        };
+        Ok((pos, adjustment))
+    }
+
+    // Next event that has not been handled yet
+    pub fn poll_event(&self) -> Option<TimedEvent> {
+        self.event_receiver.try_recv().ok()
+    }
+
+    fn emit(&self, event: TrackerEvent) {
+        let _ = self.event_sender.send(TimedEvent::now(event));
     }
 }
 
@@ -134,3 +715,99 @@ impl Drop for MouseTracker {
         *tracking = false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, y: i32, width: u32, height: u32) -> Monitor {
+        Monitor {
+            name: name.into(),
+            edid: None,
+            x,
+            y,
+            width,
+            height,
+            scale_factor: 1.0,
+            primary: false,
+        }
+    }
+
+    // A 1080p monitor with a shorter 720p one to its right, leaving a gap
+    // below the second one
+    fn monitors() -> Vec<Monitor> {
+        vec![
+            monitor("eDP-1", 0, 0, 1920, 1080),
+            monitor("HDMI-1", 1920, 0, 1280, 720),
+        ]
+    }
+
+    fn adjusted(x: i32, y: i32, policy: BoundsPolicy) -> (i32, i32) {
+        let (target, adjustment) = keep_on_screen(x, y, &monitors(), policy).unwrap();
+        let adjustment = adjustment.expect("the target should have been adjusted");
+        assert_eq!(adjustment.policy, policy);
+        assert_eq!(adjustment.from, (x, y));
+        assert_eq!(adjustment.to, target);
+        target
+    }
+
+    #[test]
+    fn targets_on_screen_are_kept() {
+        for policy in BoundsPolicy::ALL {
+            for (x, y) in [(0, 0), (1919, 1079), (1920, 0), (3199, 719)] {
+                let (target, adjustment) = keep_on_screen(x, y, &monitors(), policy).unwrap();
+                assert_eq!(target, (x, y));
+                assert!(adjustment.is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn targets_are_kept_without_monitors() {
+        for policy in BoundsPolicy::ALL {
+            let (target, adjustment) = keep_on_screen(-500, 9000, &[], policy).unwrap();
+            assert_eq!(target, (-500, 9000));
+            assert!(adjustment.is_none());
+        }
+    }
+
+    #[test]
+    fn clamp_moves_targets_into_the_desktop() {
+        assert_eq!(adjusted(4000, -200, BoundsPolicy::Clamp), (3199, 0));
+        assert_eq!(adjusted(-100, 500, BoundsPolicy::Clamp), (0, 500));
+    }
+
+    #[test]
+    fn clamp_moves_targets_in_a_gap_onto_a_monitor() {
+        // Inside the bounding box, but below the shorter monitor
+        assert_eq!(adjusted(2500, 900, BoundsPolicy::Clamp), (2500, 719));
+        // Clamped into the gap first, then onto the closer monitor
+        assert_eq!(adjusted(2500, 1500, BoundsPolicy::Clamp), (2500, 719));
+    }
+
+    #[test]
+    fn nearest_monitor_moves_targets_onto_a_monitor() {
+        assert_eq!(
+            adjusted(4000, -200, BoundsPolicy::NearestMonitor),
+            (3199, 0)
+        );
+        // Below the gap the first monitor is closer than the second
+        assert_eq!(
+            adjusted(2500, 1500, BoundsPolicy::NearestMonitor),
+            (1919, 1079)
+        );
+        assert_eq!(
+            adjusted(2500, 900, BoundsPolicy::NearestMonitor),
+            (2500, 719)
+        );
+    }
+
+    #[test]
+    fn refuse_fails_off_screen_targets() {
+        let result = keep_on_screen(2500, 900, &monitors(), BoundsPolicy::Refuse);
+        assert!(matches!(
+            result,
+            Err(RestoreError::OutOfBounds { x: 2500, y: 900 })
+        ));
+    }
+}
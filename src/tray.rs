@@ -0,0 +1,276 @@
+use egui::Context;
+use std::sync::mpsc::Sender;
+
+use crate::cli::Command;
+
+// Snapshot of the app state shown in the tray
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrayState {
+    pub tracking: bool,
+    pub recording: bool,
+    pub saved_position: Option<(i32, i32)>,
+    // Labels of the active set's tour slots, in slot list order
+    pub slots: Vec<String>,
+}
+
+// System tray icon (StatusNotifierItem on Linux)
+pub struct TraySystem {
+    #[cfg(target_os = "linux")]
+    handle: ksni::blocking::Handle<linux::MouseMinderTray>,
+    state: TrayState,
+}
+
+impl TraySystem {
+    #[cfg(target_os = "linux")]
+    pub fn new(
+        command_sender: Sender<Command>,
+        ctx: Context,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        use ksni::blocking::TrayMethods;
+
+        let state = TrayState::default();
+        let handle = linux::MouseMinderTray {
+            state: state.clone(),
+            command_sender,
+            ctx,
+        }
+        .spawn()?;
+
+        Ok(Self { handle, state })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn new(
+        _command_sender: Sender<Command>,
+        _ctx: Context,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Err("System tray is only supported on Linux".into())
+    }
+
+    // Push the latest app state to the tray if it changed
+    pub fn update(&mut self, state: TrayState) {
+        if self.state == state {
+            return;
+        }
+        self.state = state;
+
+        #[cfg(target_os = "linux")]
+        {
+            let state = self.state.clone();
+            self.handle.update(|tray| tray.state = state);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use egui::Context;
+    use ksni::menu::{StandardItem, SubMenu};
+    use ksni::{Icon, MenuItem, ToolTip};
+    use std::sync::mpsc::Sender;
+
+    use super::TrayState;
+    use crate::cli::Command;
+    use crate::config;
+
+    const ICON_SIZE: usize = 32;
+
+    pub struct MouseMinderTray {
+        pub state: TrayState,
+        pub command_sender: Sender<Command>,
+        pub ctx: Context,
+    }
+
+    impl MouseMinderTray {
+        // Hand a command to the app and wake it up to handle it
+        fn send(&self, command: Command) {
+            let _ = self.command_sender.send(command);
+            self.ctx.request_repaint();
+        }
+
+        fn status_text(&self) -> &'static str {
+            if self.state.tracking {
+                "Tracking"
+            } else {
+                "Paused"
+            }
+        }
+
+        // Filled circle in the same colors as the status indicator
+        fn status_icon(&self) -> Icon {
+            let [r, g, b] = if self.state.tracking {
+                [76, 175, 80]
+            } else {
+                [255, 152, 0]
+            };
+
+            let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+            let radius = ICON_SIZE as f32 / 2.0 - 2.0;
+            let mut data = Vec::with_capacity(ICON_SIZE * ICON_SIZE * 4);
+            for y in 0..ICON_SIZE {
+                for x in 0..ICON_SIZE {
+                    let distance = (x as f32 - center).hypot(y as f32 - center);
+                    let alpha = ((radius + 0.5 - distance).clamp(0.0, 1.0) * 255.0) as u8;
+                    data.extend_from_slice(&[alpha, r, g, b]);
+                }
+            }
+
+            Icon {
+                width: ICON_SIZE as i32,
+                height: ICON_SIZE as i32,
+                data,
+            }
+        }
+    }
+
+    impl ksni::Tray for MouseMinderTray {
+        fn id(&self) -> String {
+            env!("CARGO_PKG_NAME").into()
+        }
+
+        fn title(&self) -> String {
+            config::APP_NAME.into()
+        }
+
+        fn icon_pixmap(&self) -> Vec<Icon> {
+            vec![self.status_icon()]
+        }
+
+        fn tool_tip(&self) -> ToolTip {
+            ToolTip {
+                title: config::APP_NAME.into(),
+                description: self.status_text().into(),
+                ..Default::default()
+            }
+        }
+
+        // Left click brings the window back
+        fn activate(&mut self, _x: i32, _y: i32) {
+            self.send(Command::Show);
+        }
+
+        fn menu(&self) -> Vec<MenuItem<Self>> {
+            let (track_label, track_command) = if self.state.tracking {
+                ("Stop Tracking", Command::StopTracking)
+            } else {
+                ("Start Tracking", Command::StartTracking)
+            };
+
+            let (recording_label, recording_command) = if self.state.recording {
+                ("Stop Recording", Command::StopRecording)
+            } else {
+                ("Start Recording", Command::StartRecording)
+            };
+
+            let saved_label = match self.state.saved_position {
+                Some((x, y)) => format!("Saved: X: {}, Y: {}", x, y),
+                None => "No position saved yet".into(),
+            };
+
+            let slot_items = self
+                .state
+                .slots
+                .iter()
+                .enumerate()
+                .map(|(index, label)| {
+                    StandardItem {
+                        // Single underscores would mark access keys
+                        label: label.replace('_', "__"),
+                        activate: Box::new(move |tray: &mut Self| {
+                            tray.send(Command::RestoreSlot(index))
+                        }),
+                        ..Default::default()
+                    }
+                    .into()
+                })
+                .collect();
+
+            vec![
+                StandardItem {
+                    label: track_label.into(),
+                    activate: Box::new(move |tray: &mut Self| tray.send(track_command)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Restore Position".into(),
+                    enabled: self.state.saved_position.is_some(),
+                    activate: Box::new(|tray: &mut Self| tray.send(Command::Restore)),
+                    ..Default::default()
+                }
+                .into(),
+                SubMenu {
+                    label: "Restore to Slot".into(),
+                    enabled: !self.state.slots.is_empty(),
+                    submenu: slot_items,
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Save Current Position".into(),
+                    activate: Box::new(|tray: &mut Self| tray.send(Command::SaveNow)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Reset Position".into(),
+                    enabled: self.state.saved_position.is_some(),
+                    activate: Box::new(|tray: &mut Self| tray.send(Command::Reset)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: recording_label.into(),
+                    activate: Box::new(move |tray: &mut Self| tray.send(recording_command)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Export Session".into(),
+                    activate: Box::new(|tray: &mut Self| tray.send(Command::ExportSession)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Export Restore Markers".into(),
+                    activate: Box::new(|tray: &mut Self| tray.send(Command::ExportMarkers)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Export Cursor Path".into(),
+                    activate: Box::new(|tray: &mut Self| tray.send(Command::ExportPath)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Replay Cursor Path".into(),
+                    activate: Box::new(|tray: &mut Self| tray.send(Command::ReplayPath)),
+                    ..Default::default()
+                }
+                .into(),
+                MenuItem::Separator,
+                StandardItem {
+                    label: saved_label,
+                    enabled: false,
+                    ..Default::default()
+                }
+                .into(),
+                MenuItem::Separator,
+                StandardItem {
+                    label: "Show Window".into(),
+                    activate: Box::new(|tray: &mut Self| tray.send(Command::Show)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Quit".into(),
+                    icon_name: "application-exit".into(),
+                    activate: Box::new(|tray: &mut Self| tray.send(Command::Quit)),
+                    ..Default::default()
+                }
+                .into(),
+            ]
+        }
+    }
+}
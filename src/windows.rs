@@ -0,0 +1,513 @@
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A top-level application window and where it is on the desktop
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowInfo {
+    // X11 window id, only meaningful while the window exists
+    pub id: u32,
+    // WM_CLASS class, e.g. "Code" or "firefox"
+    pub class: String,
+    pub title: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowInfo {
+    pub fn label(&self) -> String {
+        if self.title.is_empty() {
+            self.class.clone()
+        } else {
+            format!("{} ({})", self.title, self.class)
+        }
+    }
+}
+
+// A position relative to the window it was saved over
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowAnchor {
+    pub window: WindowInfo,
+    // Offset from the window's top left corner
+    pub x: i32,
+    pub y: i32,
+}
+
+impl WindowAnchor {
+    // Anchor a point in physical pixels to the window under it
+    pub fn capture(x: i32, y: i32) -> Option<Self> {
+        let window = window_under_cursor()?;
+        Some(Self {
+            x: x - window.x,
+            y: y - window.y,
+            window,
+        })
+    }
+
+    // The same offset in the matching window's current geometry, if the
+    // window is still around
+    pub fn remap(&self) -> Option<(i32, i32)> {
+        let window = find(&self.window)?;
+        Some((window.x + self.x, window.y + self.y))
+    }
+}
+
+// The top-level window under the cursor, None over the bare desktop
+pub fn window_under_cursor() -> Option<WindowInfo> {
+    #[cfg(target_os = "linux")]
+    {
+        x11::with_display(|display| {
+            let window = display.window_under_cursor()?;
+            display.info(window)
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+// The focused top-level window
+pub fn active_window() -> Option<WindowInfo> {
+    #[cfg(target_os = "linux")]
+    {
+        x11::with_display(|display| {
+            let window = display.active_window()?;
+            display.info(window)
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+// Follows the class of the focused application. X11 does not tell us about
+// focus changes without an event loop of our own, so the active window is
+// polled, over the connection the UI thread keeps open. Our own window is
+// skipped so using MouseMinder keeps the set of the application used before it.
+pub struct FocusTracker {
+    class: Option<String>,
+    own_focused: bool,
+    last_poll: Option<Instant>,
+}
+
+impl FocusTracker {
+    pub fn new() -> Self {
+        Self {
+            class: None,
+            own_focused: false,
+            last_poll: None,
+        }
+    }
+
+    pub fn class(&self) -> Option<&str> {
+        self.class.as_deref()
+    }
+
+    // Poll the active window unless it was done recently
+    pub fn poll(&mut self, own_focused: bool) {
+        self.own_focused = own_focused;
+        let interval = Duration::from_millis(crate::config::FOCUS_POLL_INTERVAL_MS);
+        if self.last_poll.is_none_or(|last| last.elapsed() >= interval) {
+            self.refresh();
+        }
+    }
+
+    // Query the active window right away, e.g. before acting on a hotkey
+    pub fn refresh(&mut self) {
+        self.last_poll = Some(Instant::now());
+        if self.own_focused {
+            return;
+        }
+        if let Some(window) = active_window()
+            && !window.class.is_empty()
+        {
+            self.class = Some(window.class);
+        }
+    }
+}
+
+// The window a saved one refers to now: the same window if it still exists,
+// else a window of the same class, preferring one with the same title
+pub fn find(saved: &WindowInfo) -> Option<WindowInfo> {
+    #[cfg(target_os = "linux")]
+    {
+        let candidates: Vec<WindowInfo> = x11::with_display(|display| {
+            let windows = display
+                .client_list()
+                .into_iter()
+                .filter_map(|window| display.info(window))
+                .filter(|window| window.class == saved.class)
+                .collect();
+            Some(windows)
+        })?;
+
+        let by_id = candidates.iter().position(|window| window.id == saved.id);
+        let by_title = || {
+            candidates
+                .iter()
+                .position(|window| window.title == saved.title)
+        };
+        let index = by_id.or_else(by_title).unwrap_or(0);
+        candidates.into_iter().nth(index)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = saved;
+        None
+    }
+}
+
+// Raise and focus the window a saved one refers to, switching to its virtual
+// desktop first if it is on another one. The window manager does this in its
+// own time, see wait_until_active.
+pub fn focus(saved: &WindowInfo) -> Result<WindowInfo, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let window = find(saved).ok_or("The window is gone")?;
+        x11::with_display(|display| Some(display.focus(window.id)))
+            .ok_or("Could not connect to the X server")?
+            .map_err(|err| err.to_string())?;
+        Ok(window)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = saved;
+        Err("Focusing windows is only supported on X11".into())
+    }
+}
+
+// Wait for the window manager to report a window as the active one, false
+// if it did not within the timeout
+pub fn wait_until_active(window: &WindowInfo, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        #[cfg(target_os = "linux")]
+        if x11::with_display(|display| display.active_window()) == Some(window.id) {
+            return true;
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = window;
+
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use std::cell::RefCell;
+    use std::error::Error;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{
+        Atom, AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, Window,
+    };
+    use x11rb::rust_connection::RustConnection;
+
+    use super::WindowInfo;
+
+    struct Atoms {
+        wm_state: Atom,
+        net_client_list: Atom,
+        net_active_window: Atom,
+        net_wm_name: Atom,
+        net_wm_desktop: Atom,
+        net_current_desktop: Atom,
+        utf8_string: Atom,
+    }
+
+    pub struct Display {
+        conn: RustConnection,
+        root: Window,
+        atoms: Atoms,
+    }
+
+    thread_local! {
+        // Windows are looked up on every save and focus poll, keep a
+        // connection and its atoms per thread
+        static DISPLAY: RefCell<Option<Display>> = const { RefCell::new(None) };
+    }
+
+    // Run a query on this thread's connection, connecting on first use and
+    // again when the connection broke
+    pub fn with_display<T>(query: impl Fn(&Display) -> Option<T>) -> Option<T> {
+        DISPLAY.with_borrow_mut(|cached| {
+            if let Some(display) = cached.as_ref() {
+                let result = query(display);
+                if result.is_some() || display.is_alive() {
+                    return result;
+                }
+            }
+
+            let display = Display::connect().ok()?;
+            let result = query(&display);
+            *cached = Some(display);
+            result
+        })
+    }
+
+    impl Display {
+        pub fn connect() -> Result<Self, Box<dyn Error>> {
+            let (conn, screen_num) = x11rb::connect(None)?;
+            let root = conn.setup().roots[screen_num].root;
+            let atom = |name: &[u8]| -> Result<Atom, Box<dyn Error>> {
+                Ok(conn.intern_atom(false, name)?.reply()?.atom)
+            };
+            let atoms = Atoms {
+                wm_state: atom(b"WM_STATE")?,
+                net_client_list: atom(b"_NET_CLIENT_LIST")?,
+                net_active_window: atom(b"_NET_ACTIVE_WINDOW")?,
+                net_wm_name: atom(b"_NET_WM_NAME")?,
+                net_wm_desktop: atom(b"_NET_WM_DESKTOP")?,
+                net_current_desktop: atom(b"_NET_CURRENT_DESKTOP")?,
+                utf8_string: atom(b"UTF8_STRING")?,
+            };
+            Ok(Self { conn, root, atoms })
+        }
+
+        fn is_alive(&self) -> bool {
+            self.conn
+                .get_input_focus()
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .is_some()
+        }
+
+        pub fn window_under_cursor(&self) -> Option<Window> {
+            let pointer = self.conn.query_pointer(self.root).ok()?.reply().ok()?;
+            // Over the bare desktop there is no window to anchor to
+            if pointer.child == x11rb::NONE {
+                return None;
+            }
+            self.client_window(pointer.child, 0)
+        }
+
+        pub fn active_window(&self) -> Option<Window> {
+            self.property(
+                self.root,
+                self.atoms.net_active_window,
+                AtomEnum::WINDOW.into(),
+            )?
+            .first()
+            .copied()
+            .filter(|&window| window != x11rb::NONE)
+        }
+
+        // Managed windows, oldest first
+        pub fn client_list(&self) -> Vec<Window> {
+            self.property(
+                self.root,
+                self.atoms.net_client_list,
+                AtomEnum::WINDOW.into(),
+            )
+            .unwrap_or_default()
+        }
+
+        // Ask the window manager to activate the window, as a pager would
+        pub fn focus(&self, window: Window) -> Result<(), Box<dyn Error>> {
+            let desktop = self
+                .property(window, self.atoms.net_wm_desktop, AtomEnum::CARDINAL.into())
+                .and_then(|desktop| desktop.first().copied());
+            let current = self
+                .property(
+                    self.root,
+                    self.atoms.net_current_desktop,
+                    AtomEnum::CARDINAL.into(),
+                )
+                .and_then(|current| current.first().copied());
+            // 0xFFFFFFFF marks windows shown on every desktop
+            if let (Some(desktop), Some(current)) = (desktop, current)
+                && desktop != current
+                && desktop != u32::MAX
+            {
+                self.send_to_root(self.root, self.atoms.net_current_desktop, [desktop, 0, 0])?;
+            }
+
+            // Source indication 2 is a pager, which window managers obey
+            // rather than treat as focus stealing
+            self.send_to_root(window, self.atoms.net_active_window, [2, 0, 0])?;
+            self.conn.flush()?;
+            Ok(())
+        }
+
+        fn send_to_root(
+            &self,
+            window: Window,
+            message: Atom,
+            data: [u32; 3],
+        ) -> Result<(), Box<dyn Error>> {
+            let event =
+                ClientMessageEvent::new(32, window, message, [data[0], data[1], data[2], 0, 0]);
+            self.conn.send_event(
+                false,
+                self.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                event,
+            )?;
+            Ok(())
+        }
+
+        // Window managers reparent clients into frames, the client window is
+        // the one carrying WM_STATE
+        fn client_window(&self, window: Window, depth: u32) -> Option<Window> {
+            let state = self
+                .conn
+                .get_property(false, window, self.atoms.wm_state, AtomEnum::ANY, 0, 0)
+                .ok()?
+                .reply()
+                .ok()?;
+            if state.type_ != x11rb::NONE {
+                return Some(window);
+            }
+            if depth >= 3 {
+                return None;
+            }
+
+            let tree = self.conn.query_tree(window).ok()?.reply().ok()?;
+            tree.children
+                .into_iter()
+                .rev()
+                .find_map(|child| self.client_window(child, depth + 1))
+        }
+
+        pub fn info(&self, window: Window) -> Option<WindowInfo> {
+            let geometry = self.conn.get_geometry(window).ok()?.reply().ok()?;
+            let origin = self
+                .conn
+                .translate_coordinates(window, self.root, 0, 0)
+                .ok()?
+                .reply()
+                .ok()?;
+
+            // WM_CLASS holds the instance and the class, NUL separated
+            let class = self
+                .bytes(window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING.into())
+                .unwrap_or_default();
+            let class = class
+                .split(|&byte| byte == 0)
+                .nth(1)
+                .map(|class| String::from_utf8_lossy(class).into_owned())
+                .unwrap_or_default();
+
+            let title = self
+                .bytes(window, self.atoms.net_wm_name, self.atoms.utf8_string)
+                .or_else(|| self.bytes(window, AtomEnum::WM_NAME.into(), AtomEnum::STRING.into()))
+                .map(|title| String::from_utf8_lossy(&title).into_owned())
+                .unwrap_or_default();
+
+            Some(WindowInfo {
+                id: window,
+                class,
+                title,
+                x: i32::from(origin.dst_x),
+                y: i32::from(origin.dst_y),
+                width: u32::from(geometry.width),
+                height: u32::from(geometry.height),
+            })
+        }
+
+        fn property(&self, window: Window, property: Atom, type_: Atom) -> Option<Vec<u32>> {
+            let reply = self
+                .conn
+                .get_property(false, window, property, type_, 0, 4096)
+                .ok()?
+                .reply()
+                .ok()?;
+            Some(reply.value32()?.collect())
+        }
+
+        fn bytes(&self, window: Window, property: Atom, type_: Atom) -> Option<Vec<u8>> {
+            let reply = self
+                .conn
+                .get_property(false, window, property, type_, 0, 4096)
+                .ok()?
+                .reply()
+                .ok()?;
+            (reply.type_ != x11rb::NONE).then_some(reply.value)
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{
+        AtomEnum, ConnectionExt as _, CreateWindowAux, PropMode, Window, WindowClass,
+    };
+    use x11rb::wrapper::ConnectionExt as _;
+
+    // Map a top-level window of its own class and wait for the window
+    // manager to list it
+    fn open_window(conn: &impl Connection, root: Window, class: &str) -> WindowInfo {
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            x11rb::COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            200,
+            100,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            x11rb::COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )
+        .unwrap();
+        let wm_class = format!("{0}\0{0}\0", class);
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            AtomEnum::WM_CLASS,
+            AtomEnum::STRING,
+            wm_class.as_bytes(),
+        )
+        .unwrap();
+        conn.map_window(window).unwrap();
+        conn.flush().unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        loop {
+            let managed = x11::with_display(|display| Some(display.client_list()))
+                .is_some_and(|windows| windows.contains(&window));
+            if managed {
+                break;
+            }
+            assert!(Instant::now() < deadline, "{} was never managed", class);
+            thread::sleep(Duration::from_millis(20));
+        }
+        x11::with_display(|display| display.info(window)).unwrap()
+    }
+
+    // Run with an X server and window manager, e.g. Xvfb and openbox as in
+    // the README: DISPLAY=:99 cargo test -- --ignored
+    #[test]
+    #[ignore = "needs an X server with an EWMH window manager"]
+    fn focus_sets_the_active_window() {
+        if std::env::var_os("DISPLAY").is_none() {
+            return;
+        }
+        let (conn, screen_num) = x11rb::connect(None).unwrap();
+        let root = conn.setup().roots[screen_num].root;
+        let first = open_window(&conn, root, "MouseMinderTestA");
+        let second = open_window(&conn, root, "MouseMinderTestB");
+
+        for window in [&first, &second, &first] {
+            let focused = focus(window).unwrap();
+            assert_eq!(focused.id, window.id);
+            assert!(wait_until_active(window, Duration::from_secs(2)));
+            let active = x11::with_display(|display| display.active_window());
+            assert_eq!(active, Some(window.id));
+        }
+    }
+}